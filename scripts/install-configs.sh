@@ -38,7 +38,7 @@ check_configs() {
     
     local missing_files=()
     
-    for config_file in "file-types.toml" "categories.toml" "commit-templates.toml"; do
+    for config_file in "file-types.toml" "categories.toml" "commit-templates.toml" "settings.toml"; do
         if [ ! -f "$CONFIGS_DIR/$config_file" ]; then
             missing_files+=("$config_file")
         fi
@@ -61,7 +61,7 @@ install_to_user() {
     
     mkdir -p "$USER_CONFIG_DIR"
     
-    for config_file in "file-types.toml" "categories.toml" "commit-templates.toml"; do
+    for config_file in "file-types.toml" "categories.toml" "commit-templates.toml" "settings.toml"; do
         if cp "$CONFIGS_DIR/$config_file" "$USER_CONFIG_DIR/"; then
             echo -e "  ${GREEN}✓${NC} $config_file"
         else
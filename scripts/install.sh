@@ -323,7 +323,7 @@ install_configs_from_dir() {
         chown -R "$SUDO_USER:$(id -gn $SUDO_USER)" "$actual_home/.cyber-zen"
     fi
 
-    local config_files=("file-types.toml" "categories.toml" "commit-templates.toml")
+    local config_files=("file-types.toml" "categories.toml" "commit-templates.toml" "settings.toml")
     local success_count=0
 
     print_info "从 $source_dir 复制配置文件到 $user_config_dir..."
@@ -398,7 +398,7 @@ install_configs() {
         # 如果本地没有，尝试从远程下载
         print_info "未找到本地配置文件，尝试从远程下载..."
         local base_url="https://raw.githubusercontent.com/hex2rgb/cyber-zen-tools-rust/main/configs"
-        local config_files=("file-types.toml" "categories.toml" "commit-templates.toml")
+        local config_files=("file-types.toml" "categories.toml" "commit-templates.toml" "settings.toml")
         local success_count=0
 
         for config_file in "${config_files[@]}"; do
@@ -0,0 +1,381 @@
+use colored::*;
+use crate::config::FileTypeManager;
+use crate::git::{check_git_repo, git_output};
+use crate::i18n::{commit_lang, Lang};
+use regex::Regex;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// 分组的展示顺序，未列出的类型按名称排在后面
+const TYPE_ORDER: &[&str] = &[
+    "feat", "fix", "perf", "refactor", "revert", "docs", "style", "test", "build", "ci", "chore", "cleanup",
+];
+
+const CHANGELOG_TITLE: &str = "# Changelog";
+
+/// 解析后的 Conventional Commit
+pub(crate) struct ConventionalCommit {
+    pub hash: String,
+    pub short_hash: String,
+    pub author: String,
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub summary: String,
+    pub breaking: bool,
+    /// `BREAKING CHANGE:` footer 中的说明
+    pub breaking_note: Option<String>,
+}
+
+pub fn run_changelog(
+    from: Option<String>,
+    to: String,
+    version: Option<String>,
+    output: String,
+    stdout: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let from = from.or_else(|| previous_tag(repo, &to));
+    let version = version
+        .or_else(|| exact_tag(repo, &to))
+        .unwrap_or_else(|| "Unreleased".to_string());
+
+    let (commits, skipped) = collect_commits(repo, from.as_deref(), &to)?;
+
+    let file_type_manager = FileTypeManager::new()?;
+    let section = render_section(repo, &file_type_manager, &version, from.as_deref(), &to, &commits);
+
+    if stdout {
+        println!("{}", section);
+        return Ok(());
+    }
+
+    let range = match &from {
+        Some(f) => format!("{}..{}", f, to),
+        None => to.clone(),
+    };
+    println!("{} {}", t!("changelog.range").cyan(), range);
+    println!("{} {}", t!("changelog.commits").cyan(), t!("changelog.commit_count", count = commits.len(), skipped = skipped));
+
+    write_changelog(Path::new(&output), &version, &section, is_head(repo, &to))?;
+    println!("{} {}", t!("changelog.updated").green(), output);
+    Ok(())
+}
+
+/// `to` 之前最近的标签（不含 `to` 自身的标签）
+pub(crate) fn previous_tag(repo: &Path, to: &str) -> Option<String> {
+    git_output(repo, &["describe", "--tags", "--abbrev=0", &format!("{}^", to)])
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// `rev` 与 HEAD 指向同一个提交
+fn is_head(repo: &Path, rev: &str) -> bool {
+    let resolve = |r: &str| git_output(repo, &["rev-parse", "--verify", "-q", &format!("{}^{{commit}}", r)]).ok();
+    match (resolve(rev), resolve("HEAD")) {
+        (Some(a), Some(b)) => a.trim() == b.trim(),
+        _ => false,
+    }
+}
+
+fn exact_tag(repo: &Path, rev: &str) -> Option<String> {
+    git_output(repo, &["describe", "--tags", "--exact-match", rev])
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// 读取 `from..to` 范围内的提交，返回规范提交和被跳过的非规范提交数
+pub(crate) fn collect_commits(
+    repo: &Path,
+    from: Option<&str>,
+    to: &str,
+) -> Result<(Vec<ConventionalCommit>, usize), Box<dyn std::error::Error>> {
+    let range = match from {
+        Some(f) => format!("{}..{}", f, to),
+        None => to.to_string(),
+    };
+
+    let log = git_output(
+        repo,
+        &["log", "--no-merges", "--format=%H%x1f%h%x1f%an%x1f%s%x1f%b%x1e", &range],
+    )?;
+
+    let mut commits = Vec::new();
+    let mut skipped = 0;
+
+    for record in log.split('\x1e') {
+        let fields: Vec<&str> = record.trim_start_matches('\n').split('\x1f').collect();
+        if fields.len() < 5 {
+            continue;
+        }
+
+        match parse_conventional(fields[3], fields[4]) {
+            Some((commit_type, scope, breaking, summary, breaking_note)) => commits.push(ConventionalCommit {
+                hash: fields[0].to_string(),
+                short_hash: fields[1].to_string(),
+                author: fields[2].to_string(),
+                commit_type,
+                scope,
+                summary,
+                breaking,
+                breaking_note,
+            }),
+            None => skipped += 1,
+        }
+    }
+
+    Ok((commits, skipped))
+}
+
+type ParsedHeader = (String, Option<String>, bool, String, Option<String>);
+
+/// 解析 `type(scope)!: summary`，正文中的 `BREAKING CHANGE:` footer 同样视为破坏性变更；
+/// 与 commit-msg hook 一致，允许 gitmoji 等风格在类型前带一个 emoji
+pub(crate) fn parse_conventional(subject: &str, body: &str) -> Option<ParsedHeader> {
+    let re = Regex::new(r"^(?:[^\x00-\x7F]+\s+)?([a-zA-Z]+)(?:\(([^)]*)\))?(!)?: (.+)$").ok()?;
+    let caps = re.captures(subject.trim())?;
+
+    let commit_type = caps[1].to_lowercase();
+    let scope = caps.get(2).map(|m| m.as_str().to_string()).filter(|s| !s.is_empty());
+    let summary = caps[4].trim().to_string();
+
+    let breaking_note = body.lines().find_map(|line| {
+        line.strip_prefix("BREAKING CHANGE:")
+            .or_else(|| line.strip_prefix("BREAKING-CHANGE:"))
+            .map(|note| note.trim().to_string())
+    });
+    let breaking = caps.get(3).is_some() || breaking_note.is_some();
+
+    Some((commit_type, scope, breaking, summary, breaking_note))
+}
+
+/// 由 origin 地址推导网页地址，支持 `git@host:owner/repo.git` 和 `https://host/owner/repo.git`
+fn remote_web_url(repo: &Path) -> Option<String> {
+    let url = git_output(repo, &["remote", "get-url", "origin"]).ok()?;
+    let url = url.trim().trim_end_matches(".git");
+
+    if let Some(rest) = url.strip_prefix("git@") {
+        let (host, path) = rest.split_once(':')?;
+        return Some(format!("https://{}/{}", host, path));
+    }
+    if let Some(rest) = url.strip_prefix("ssh://git@") {
+        return Some(format!("https://{}", rest));
+    }
+    if url.starts_with("https://") || url.starts_with("http://") {
+        return Some(url.to_string());
+    }
+    None
+}
+
+/// 分组标题：中文取 commit-templates.toml 中的描述，其他语言取内置文本
+pub(crate) fn type_heading(file_type_manager: &FileTypeManager, lang: Lang, commit_type: &str) -> String {
+    match lang {
+        Lang::ZhCn => file_type_manager.get_commit_description(commit_type),
+        lang => {
+            let key = format!("changelog.types.{}", commit_type);
+            match crate::i18n::lookup(lang, &key) {
+                text if text == key => commit_type.to_string(),
+                text => text.to_string(),
+            }
+        }
+    }
+}
+
+/// 按类型分组，顺序见 TYPE_ORDER
+pub(crate) fn group_by_type(commits: &[ConventionalCommit]) -> Vec<(&str, Vec<&ConventionalCommit>)> {
+    let mut groups: HashMap<&str, Vec<&ConventionalCommit>> = HashMap::new();
+    for commit in commits {
+        groups.entry(commit.commit_type.as_str()).or_default().push(commit);
+    }
+
+    let mut groups: Vec<(&str, Vec<&ConventionalCommit>)> = groups.into_iter().collect();
+    groups.sort_by_key(|(t, _)| {
+        let pos = TYPE_ORDER.iter().position(|o| o == t).unwrap_or(TYPE_ORDER.len());
+        (pos, t.to_string())
+    });
+    groups
+}
+
+pub(crate) fn render_section(
+    repo: &Path,
+    file_type_manager: &FileTypeManager,
+    version: &str,
+    from: Option<&str>,
+    to: &str,
+    commits: &[ConventionalCommit],
+) -> String {
+    let web_url = remote_web_url(repo);
+    let date = git_output(repo, &["log", "-1", "--format=%cs", to])
+        .map(|s| s.trim().to_string())
+        .unwrap_or_default();
+
+    let title = match (&web_url, from) {
+        (Some(url), Some(from)) if version != "Unreleased" => {
+            format!("## [{}]({}/compare/{}...{})", version, url, from, version)
+        }
+        _ => format!("## {}", version),
+    };
+
+    let mut lines = vec![if date.is_empty() { title } else { format!("{} - {}", title, date) }];
+
+    let breaking: Vec<&ConventionalCommit> = commits.iter().filter(|c| c.breaking).collect();
+    if !breaking.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("### ⚠ {}", type_heading(file_type_manager, commit_lang(), "breaking")));
+        lines.push(String::new());
+        for commit in breaking {
+            lines.push(render_entry(commit, web_url.as_deref()));
+            if let Some(note) = &commit.breaking_note {
+                lines.push(format!("  {}", note));
+            }
+        }
+    }
+
+    for (commit_type, group) in group_by_type(commits) {
+        lines.push(String::new());
+        lines.push(format!("### {}", type_heading(file_type_manager, commit_lang(), commit_type)));
+        lines.push(String::new());
+        for commit in group {
+            lines.push(render_entry(commit, web_url.as_deref()));
+        }
+    }
+
+    if commits.is_empty() {
+        lines.push(String::new());
+        lines.push(tc!("changelog.empty"));
+    }
+
+    lines.join("\n")
+}
+
+fn render_entry(commit: &ConventionalCommit, web_url: Option<&str>) -> String {
+    let scope = commit
+        .scope
+        .as_ref()
+        .map(|s| format!("**{}:** ", s))
+        .unwrap_or_default();
+    let link = match web_url {
+        Some(url) => format!("[{}]({}/commit/{})", commit.short_hash, url, commit.hash),
+        None => commit.short_hash.clone(),
+    };
+
+    format!("- {}{} ({}) - {}", scope, commit.summary, link, commit.author)
+}
+
+/// 已存在同版本的段落时整体替换，否则按日期和版本号插入到对应位置；Unreleased 始终在最前
+pub(crate) fn write_changelog(path: &Path, version: &str, section: &str, at_head: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let existing = fs::read_to_string(path).unwrap_or_default();
+    let existing = existing.trim_end();
+
+    let mut sections: Vec<String> = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut header: Vec<&str> = Vec::new();
+
+    for line in existing.lines() {
+        if line.starts_with("## ") {
+            if !current.is_empty() {
+                sections.push(current.join("\n").trim_end().to_string());
+            }
+            current = vec![line];
+        } else if current.is_empty() {
+            header.push(line);
+        } else {
+            current.push(line);
+        }
+    }
+    if !current.is_empty() {
+        sections.push(current.join("\n").trim_end().to_string());
+    }
+
+    // 范围截止到 HEAD 的版本已包含原 Unreleased 段落的内容；重新生成历史版本时保留它
+    if version != "Unreleased" && at_head {
+        sections.retain(|s| section_version(s) != "Unreleased");
+    }
+
+    match sections.iter().position(|s| section_version(s) == version) {
+        Some(index) => sections[index] = section.to_string(),
+        None if version == "Unreleased" => sections.insert(0, section.to_string()),
+        None => {
+            let order = section_order(section);
+            let index = sections
+                .iter()
+                .position(|s| section_version(s) != "Unreleased" && section_order(s) < order)
+                .unwrap_or(sections.len());
+            sections.insert(index, section.to_string());
+        }
+    }
+
+    let header = header.join("\n");
+    let header = if header.trim().is_empty() {
+        CHANGELOG_TITLE.to_string()
+    } else {
+        header.trim_end().to_string()
+    };
+
+    let content = format!("{}\n\n{}\n", header, sections.join("\n\n"));
+    fs::write(path, content)?;
+    Ok(())
+}
+
+/// 从 "## [v1.2.0](...) - 2024-01-01" 或 "## Unreleased" 中取出版本名
+fn section_version(section: &str) -> &str {
+    let title = section_title(section);
+    let title = title.split(" - ").next().unwrap_or(title);
+
+    match title.strip_prefix('[') {
+        Some(rest) => rest.split(']').next().unwrap_or(rest),
+        None => title.trim(),
+    }
+}
+
+fn section_title(section: &str) -> &str {
+    section.lines().next().unwrap_or("").trim_start_matches("## ")
+}
+
+/// 段落的排序依据：先比较日期，同一天再比较版本号中的数字
+fn section_order(section: &str) -> (String, Vec<u64>) {
+    let date = section_title(section).split(" - ").nth(1).unwrap_or("").trim().to_string();
+    let numbers = section_version(section)
+        .split(|c: char| !c.is_ascii_digit())
+        .filter_map(|n| n.parse().ok())
+        .collect();
+    (date, numbers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_header() {
+        let (commit_type, scope, breaking, summary, _) = parse_conventional("feat(api): add search", "").unwrap();
+        assert_eq!(commit_type, "feat");
+        assert_eq!(scope.as_deref(), Some("api"));
+        assert!(!breaking);
+        assert_eq!(summary, "add search");
+    }
+
+    #[test]
+    fn parses_gitmoji_header() {
+        let (commit_type, scope, breaking, summary, _) = parse_conventional("✨ feat(api): add search", "").unwrap();
+        assert_eq!(commit_type, "feat");
+        assert_eq!(scope.as_deref(), Some("api"));
+        assert!(!breaking);
+        assert_eq!(summary, "add search");
+
+        let (commit_type, _, breaking, summary, _) = parse_conventional("💥 feat!: drop v1 endpoints", "").unwrap();
+        assert_eq!(commit_type, "feat");
+        assert!(breaking);
+        assert_eq!(summary, "drop v1 endpoints");
+    }
+
+    #[test]
+    fn rejects_non_conventional_header() {
+        assert!(parse_conventional("Update README", "").is_none());
+        assert!(parse_conventional("✨ Update README", "").is_none());
+    }
+}
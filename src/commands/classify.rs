@@ -0,0 +1,48 @@
+use colored::*;
+use crate::config::FileTypeManager;
+use std::path::Path;
+
+pub fn run_classify(paths: Vec<String>, explain: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let file_type_manager = FileTypeManager::new()?;
+    let root = Path::new(".");
+
+    for path in &paths {
+        let category = file_type_manager.get_file_category(root, path);
+        let file_type = file_type_manager.get_file_type(root, path);
+
+        if !explain {
+            println!("{}  {} / {}", path, category.green(), file_type.cyan());
+            continue;
+        }
+
+        println!("{}", path.yellow());
+        println!("  {} {}", t!("classify.category").cyan(), category.green());
+        println!("  {} {}", t!("classify.file_type").cyan(), file_type);
+
+        let matches = file_type_manager.explain_file_category(root, path);
+        if matches.is_empty() {
+            println!(
+                "  {} {}",
+                t!("classify.rules").cyan(),
+                t!("classify.no_match", category = file_type_manager.get_default_category())
+            );
+        } else {
+            println!("  {}", t!("classify.rules").cyan());
+            for (i, m) in matches.iter().enumerate() {
+                let marker = if i == 0 { "✓".green() } else { " ".normal() };
+                println!(
+                    "    {} {} {} \"{}\" {} → {}",
+                    marker,
+                    m.rule,
+                    m.kind,
+                    m.pattern,
+                    t!("classify.priority", priority = m.priority),
+                    m.description
+                );
+            }
+        }
+        println!();
+    }
+
+    Ok(())
+}
@@ -0,0 +1,337 @@
+use super::emit::{self, to_web_path, Conversion};
+use super::manifest::{self, Manifest, ManifestEntry};
+use super::{compress_image_file, converted_path, format_size, is_image_file, ratio, CompressOptions, OutputFormat, Outcome};
+use crate::commands::{display_width, pad};
+use colored::*;
+use indicatif::{ProgressBar, ProgressStyle};
+use rayon::prelude::*;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// 目录中单个文件的处理结果；错误转为字符串以便跨线程收集
+struct FileResult {
+    rel_path: PathBuf,
+    result: Result<Outcome, String>,
+    /// 增量模式下源文件的哈希
+    hash: Option<String>,
+}
+
+/// 并行压缩目录中的图片，结束后输出汇总；有文件失败时返回错误
+pub fn compress_directory(src_dir: &Path, dist_dir: &Path, options: &CompressOptions) -> Result<(), Box<dyn std::error::Error>> {
+    println!("{} {}", t!("compress.directory").yellow(), src_dir.display());
+
+    fs::create_dir_all(dist_dir)?;
+
+    let mut files = Vec::new();
+    collect_images(src_dir, &mut files)?;
+    files.sort();
+    let outputs = plan_outputs(&files, src_dir, dist_dir, options.format)?;
+
+    // num_threads(0) 按 CPU 核数创建线程
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.jobs.unwrap_or(0))
+        .build()?;
+    println!("{} {}", t!("compress.jobs").cyan(), pool.current_num_threads());
+
+    let progress = ProgressBar::new(files.len() as u64);
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} {elapsed_precise} {msg}")?.progress_chars("=> "),
+    );
+    let saved = AtomicI64::new(0);
+
+    let manifest = if options.incremental { Some(Manifest::load(dist_dir)?) } else { None };
+    let fingerprint = manifest::fingerprint(options);
+
+    let results: Vec<FileResult> = pool.install(|| {
+        files
+            .par_iter()
+            .zip(outputs.par_iter())
+            .map(|(path, output)| {
+                let rel_path = path.strip_prefix(src_dir).unwrap_or(path).to_path_buf();
+                let (result, hash) = match &manifest {
+                    Some(manifest) => match fs::read(path) {
+                        Ok(data) => {
+                            let hash = manifest::hash_bytes(&data);
+                            let key = to_web_path(&rel_path);
+                            let result = match manifest.unchanged(&key, &hash, &fingerprint, dist_dir) {
+                                Some(entry) => Ok(skipped(entry, data.len() as u64, dist_dir)),
+                                None => compress_in_directory(path, output, options),
+                            };
+                            (result.map_err(|e| e.to_string()), Some(hash))
+                        }
+                        Err(e) => (Err(e.to_string()), None),
+                    },
+                    None => (compress_in_directory(path, output, options).map_err(|e| e.to_string()), None),
+                };
+
+                if let Ok(outcome) = &result {
+                    // --max-bytes 为每个文件选用的质量不同，逐个列出（保留原文件时质量无意义）；suspend 避免与进度条交错，且在非终端下进度条隐藏时同样输出
+                    if let Some(quality) = outcome.chosen_quality.as_ref().filter(|_| !outcome.kept) {
+                        progress.suspend(|| {
+                            println!(
+                                "  {}",
+                                t!(
+                                    "compress.file_chosen_quality",
+                                    path = rel_path.display(),
+                                    quality = quality,
+                                    size = format_size(outcome.new_size)
+                                )
+                            )
+                        });
+                    }
+                    let delta = outcome.original_size as i64 - outcome.new_size as i64;
+                    let total = saved.fetch_add(delta, Ordering::Relaxed) + delta;
+                    progress.set_message(t!("compress.progress_saved", size = format_signed_size(total)));
+                }
+                progress.inc(1);
+
+                FileResult { rel_path, result, hash }
+            })
+            .collect()
+    });
+    progress.finish_and_clear();
+
+    println!("{} {}", t!("compress.directory_done").green(), dist_dir.display());
+
+    if let Some(kind) = options.emit {
+        let conversions: Vec<Conversion> = results
+            .iter()
+            .filter_map(|r| {
+                let outcome = r.result.as_ref().ok()?;
+                let converted = outcome.written.strip_prefix(dist_dir).ok()?;
+                (converted.extension() != r.rel_path.extension()).then(|| Conversion::new(src_dir, &r.rel_path, dist_dir, converted))
+            })
+            .collect();
+        let path = emit::write_conversions(dist_dir, kind, &conversions)?;
+        println!("{} {}", t!("compress.emitted").green(), path.display());
+    }
+
+    if let Some(manifest) = manifest {
+        update_manifest(dist_dir, manifest, &results, &fingerprint)?;
+    }
+
+    let failed = print_summary(&results);
+    if failed > 0 {
+        return Err(t!("compress.some_failed", count = failed).into());
+    }
+    Ok(())
+}
+
+fn collect_images(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_images(&path, files)?;
+        } else if is_image_file(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 计算每个源文件的输出路径。--format 会让 a.png 和 a.jpg 都映射到 a.webp，
+/// 并行写入时只剩最后一个，因此重名时改变了扩展名的文件改用 a.png.webp
+fn plan_outputs(
+    files: &[PathBuf],
+    src_dir: &Path,
+    dist_dir: &Path,
+    format: Option<OutputFormat>,
+) -> Result<Vec<PathBuf>, Box<dyn std::error::Error>> {
+    let targets: Vec<PathBuf> = files
+        .iter()
+        .map(|path| converted_path(&dist_dir.join(path.strip_prefix(src_dir).unwrap_or(path)), format))
+        .collect();
+    let mut counts: HashMap<&PathBuf, usize> = HashMap::new();
+    for target in &targets {
+        *counts.entry(target).or_default() += 1;
+    }
+
+    let mut outputs = Vec::with_capacity(files.len());
+    for (path, target) in files.iter().zip(&targets) {
+        if counts[target] < 2 || path.extension() == target.extension() {
+            outputs.push(target.clone());
+            continue;
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let extension = target.extension().unwrap_or_default().to_string_lossy();
+        let output = target.with_file_name(format!("{}.{}", name, extension));
+        println!(
+            "{} {} → {}",
+            t!("compress.renamed_output").yellow(),
+            path.strip_prefix(src_dir).unwrap_or(path).display(),
+            output.strip_prefix(dist_dir).unwrap_or(&output).display()
+        );
+        outputs.push(output);
+    }
+
+    // 改名后仍重名（源目录中本就有 a.png.webp）时无法自动区分
+    let mut seen: HashMap<&PathBuf, &PathBuf> = HashMap::new();
+    for (path, output) in files.iter().zip(&outputs) {
+        if let Some(other) = seen.insert(output, path) {
+            return Err(t!(
+                "compress.output_collision",
+                first = other.display(),
+                second = path.display(),
+                output = output.display()
+            )
+            .into());
+        }
+    }
+
+    Ok(outputs)
+}
+
+fn compress_in_directory(src_file: &Path, dist_file: &Path, options: &CompressOptions) -> Result<Outcome, Box<dyn std::error::Error>> {
+    if let Some(parent) = dist_file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    compress_image_file(src_file, dist_file, options)
+}
+
+fn skipped(entry: &ManifestEntry, original_size: u64, dist_dir: &Path) -> Outcome {
+    Outcome {
+        written: dist_dir.join(&entry.output),
+        original_size,
+        new_size: entry.size,
+        dimensions: None,
+        chosen_quality: None,
+        copied: None,
+        kept: false,
+        skipped: true,
+    }
+}
+
+/// 记录本次的结果，并删除源文件已不存在或输出路径已变化的旧输出
+fn update_manifest(
+    dist_dir: &Path,
+    old: Manifest,
+    results: &[FileResult],
+    fingerprint: &str,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut manifest = Manifest::default();
+    let sources: BTreeSet<String> = results.iter().map(|r| to_web_path(&r.rel_path)).collect();
+
+    for r in results {
+        let key = to_web_path(&r.rel_path);
+        match (&r.result, &r.hash) {
+            (Ok(outcome), Some(hash)) => {
+                let output = outcome.written.strip_prefix(dist_dir).unwrap_or(&outcome.written);
+                manifest.files.insert(
+                    key,
+                    ManifestEntry {
+                        hash: hash.clone(),
+                        options: fingerprint.to_string(),
+                        output: to_web_path(output),
+                        size: outcome.new_size,
+                    },
+                );
+            }
+            // 失败的文件保留旧记录，参数或内容变化后会重新处理
+            _ => {
+                if let Some(entry) = old.files.get(&key) {
+                    manifest.files.insert(key, entry.clone());
+                }
+            }
+        }
+    }
+
+    // 多个旧记录可能指向同一个输出（如改名前 a.png 和 a.jpg 都输出为 a.webp），仍被引用的输出不能删除
+    let outputs: BTreeSet<&str> = manifest.files.values().map(|entry| entry.output.as_str()).collect();
+    let mut pruned = 0;
+    for (key, entry) in &old.files {
+        let replaced = manifest.files.get(key).is_some_and(|current| current.output != entry.output);
+        let stale = !sources.contains(key) || replaced;
+        if stale && !outputs.contains(entry.output.as_str()) && fs::remove_file(dist_dir.join(&entry.output)).is_ok() {
+            pruned += 1;
+        }
+    }
+
+    manifest.save(dist_dir)?;
+    println!(
+        "{} {}",
+        t!("compress.manifest_updated").green(),
+        t!("compress.manifest_stats", pruned = pruned)
+    );
+    Ok(())
+}
+
+fn format_signed_size(bytes: i64) -> String {
+    if bytes < 0 {
+        format!("-{}", format_size(bytes.unsigned_abs()))
+    } else {
+        format_size(bytes as u64)
+    }
+}
+
+/// 输出汇总表和失败列表，返回失败数
+fn print_summary(results: &[FileResult]) -> usize {
+    let succeeded: Vec<(&PathBuf, &Outcome)> = results
+        .iter()
+        .filter_map(|r| r.result.as_ref().ok().map(|outcome| (&r.rel_path, outcome)))
+        .collect();
+    let failures: Vec<(&PathBuf, &String)> = results
+        .iter()
+        .filter_map(|r| r.result.as_ref().err().map(|error| (&r.rel_path, error)))
+        .collect();
+    let copied = succeeded.iter().filter(|(_, outcome)| outcome.copied.is_some()).count();
+    let kept = succeeded.iter().filter(|(_, outcome)| outcome.kept).count();
+    let skipped = succeeded.iter().filter(|(_, outcome)| outcome.skipped).count();
+
+    let before: u64 = succeeded.iter().map(|(_, outcome)| outcome.original_size).sum();
+    let after: u64 = succeeded.iter().map(|(_, outcome)| outcome.new_size).sum();
+    let saved = before as i64 - after as i64;
+    let saved_percent = if before > 0 { saved as f64 / before as f64 * 100.0 } else { 0.0 };
+
+    // 原样复制、保留原文件和跳过的不参与比率排名
+    let compressed = || {
+        succeeded
+            .iter()
+            .filter(|(_, outcome)| outcome.copied.is_none() && !outcome.kept && !outcome.skipped)
+    };
+    let best = compressed().min_by(|a, b| ratio(a.1).total_cmp(&ratio(b.1)));
+    let worst = compressed().max_by(|a, b| ratio(a.1).total_cmp(&ratio(b.1)));
+    let ratio_cell = |entry: Option<&&(&PathBuf, &Outcome)>| match entry {
+        Some((path, outcome)) => format!("{:.2}% ({})", ratio(outcome) * 100.0, path.display()),
+        None => "-".to_string(),
+    };
+
+    let rows = [
+        (
+            t!("compress.summary_count"),
+            t!(
+                "compress.summary_count_value",
+                total = results.len(),
+                compressed = succeeded.len() - copied - kept - skipped,
+                kept = kept,
+                skipped = skipped,
+                copied = copied,
+                failed = failures.len()
+            ),
+        ),
+        (t!("compress.summary_before"), format_size(before)),
+        (t!("compress.summary_after"), format_size(after)),
+        (t!("compress.summary_saved"), format!("{} ({:.2}%)", format_signed_size(saved), saved_percent)),
+        (t!("compress.summary_best"), ratio_cell(best.as_ref())),
+        (t!("compress.summary_worst"), ratio_cell(worst.as_ref())),
+    ];
+
+    let width = rows.iter().map(|(label, _)| display_width(label)).max().unwrap_or(0);
+    println!();
+    println!("{}", t!("compress.summary").cyan());
+    for (label, value) in &rows {
+        println!("  {}  {}", pad(label, width).yellow(), value);
+    }
+
+    if !failures.is_empty() {
+        println!();
+        println!("{}", t!("compress.summary_failures").red());
+        for (path, error) in &failures {
+            println!("  {} - {}", path.display(), error);
+        }
+    }
+    println!();
+
+    failures.len()
+}
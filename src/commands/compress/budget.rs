@@ -0,0 +1,119 @@
+use super::encode::{self, EncodeSettings};
+use super::resize::{resize_image, ResizeSettings};
+use image::{DynamicImage, ImageFormat};
+
+/// 尺寸最多缩小到原图的这个比例，再小通常已失去使用价值
+const MIN_BUDGET_SCALE: f64 = 0.25;
+
+/// 每轮缩小尺寸的比例
+const BUDGET_SCALE_STEP: f64 = 0.85;
+
+/// 满足体积上限的编码结果
+pub struct Fitted {
+    pub data: Vec<u8>,
+    /// 选中的质量；无损格式为 None
+    pub quality: Option<u8>,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// 解析 `200KB`、`1.5MB`、`500000` 这类体积，单位按 1024 进位
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    let value = value.trim();
+    let split = value.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+
+    let multiplier = match unit.trim().to_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1024,
+        "M" | "MB" | "MIB" => 1024 * 1024,
+        _ => return Err(t!("compress.invalid_size", value = value)),
+    };
+
+    match number.parse::<f64>() {
+        Ok(n) if n > 0.0 => Ok((n * multiplier as f64) as u64),
+        _ => Err(t!("compress.invalid_size", value = value)),
+    }
+}
+
+/// 先在 [min_quality, settings.quality] 内搜索能满足上限的最高质量，仍然超出时逐步缩小尺寸
+pub fn fit_to_budget(
+    img: &DynamicImage,
+    format: ImageFormat,
+    settings: &EncodeSettings,
+    resize: &ResizeSettings,
+    max_bytes: u64,
+    min_quality: u8,
+) -> Result<Fitted, Box<dyn std::error::Error>> {
+    let (width, height) = (img.width(), img.height());
+    let mut scale = 1.0;
+    let mut smallest = u64::MAX;
+
+    while scale >= MIN_BUDGET_SCALE {
+        let candidate = if scale < 1.0 {
+            let new_width = ((width as f64 * scale) as u32).max(1);
+            let new_height = ((height as f64 * scale) as u32).max(1);
+            resize_image(img, new_width, new_height, resize)
+        } else {
+            img.clone()
+        };
+
+        let (data, quality) = if encode::uses_quality(format, settings) {
+            search_quality(&candidate, format, settings, max_bytes, min_quality)?
+        } else {
+            (encode::encode(&candidate, format, settings)?, None)
+        };
+
+        if data.len() as u64 <= max_bytes {
+            return Ok(Fitted { data, quality, width: candidate.width(), height: candidate.height() });
+        }
+        smallest = smallest.min(data.len() as u64);
+        scale *= BUDGET_SCALE_STEP;
+    }
+
+    Err(t!(
+        "compress.budget_unreachable",
+        limit = max_bytes,
+        smallest = smallest,
+        min_quality = min_quality
+    )
+    .into())
+}
+
+/// 二分查找不超过上限的最高质量；最低质量仍超出时返回最低质量的结果，由调用方继续缩小尺寸
+fn search_quality(
+    img: &DynamicImage,
+    format: ImageFormat,
+    settings: &EncodeSettings,
+    max_bytes: u64,
+    min_quality: u8,
+) -> Result<(Vec<u8>, Option<u8>), Box<dyn std::error::Error>> {
+    let encode_at = |quality: u8| encode::encode(img, format, &EncodeSettings { quality, ..*settings });
+
+    let max_quality = settings.quality.max(min_quality);
+    let best = encode_at(max_quality)?;
+    if best.len() as u64 <= max_bytes || max_quality == min_quality {
+        return Ok((best, Some(max_quality)));
+    }
+
+    let floor = encode_at(min_quality)?;
+    if floor.len() as u64 > max_bytes {
+        return Ok((floor, Some(min_quality)));
+    }
+
+    // 不变式：low 满足上限，high 超出上限
+    let (mut low, mut high) = (min_quality, max_quality);
+    let mut fitted = floor;
+    while high - low > 1 {
+        let mid = low + (high - low) / 2;
+        let data = encode_at(mid)?;
+        if data.len() as u64 <= max_bytes {
+            low = mid;
+            fitted = data;
+        } else {
+            high = mid;
+        }
+    }
+
+    Ok((fitted, Some(low)))
+}
@@ -0,0 +1,135 @@
+use clap::ValueEnum;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// 格式转换后额外输出的映射文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum EmitKind {
+    /// `<picture>` 代码片段，写入 picture.html
+    Picture,
+    /// 原路径到转换后路径的映射，写入 conversions.json
+    Json,
+}
+
+/// 一次格式转换，路径使用 `/` 分隔
+pub struct Conversion {
+    /// 相对于源目录，作为 conversions.json 的键
+    pub original: String,
+    /// 原图相对于目标目录的路径，与 `converted` 同一基准，供 `<img src>` 使用
+    pub original_href: String,
+    /// 相对于目标目录
+    pub converted: String,
+}
+
+impl Conversion {
+    pub fn new(source_dir: &Path, original: &Path, output_dir: &Path, converted: &Path) -> Self {
+        Conversion {
+            original: to_web_path(original),
+            original_href: to_web_path(&relative_path(output_dir, &source_dir.join(original))),
+            converted: to_web_path(converted),
+        }
+    }
+}
+
+/// `target` 相对于目录 `base` 的路径，如 `dist` 到 `images/a.jpg` 为 `../images/a.jpg`
+fn relative_path(base: &Path, target: &Path) -> PathBuf {
+    // 相对路径的文件名取 parent() 会得到空路径，即当前目录
+    let base = if base.as_os_str().is_empty() { Path::new(".") } else { base };
+    let base = base.canonicalize().unwrap_or_else(|_| base.to_path_buf());
+    let target = target.canonicalize().unwrap_or_else(|_| target.to_path_buf());
+
+    let base: Vec<Component> = base.components().collect();
+    let target: Vec<Component> = target.components().collect();
+    let common = base.iter().zip(&target).take_while(|(a, b)| a == b).count();
+
+    let mut path = PathBuf::new();
+    for _ in common..base.len() {
+        path.push("..");
+    }
+    for component in &target[common..] {
+        path.push(component);
+    }
+    path
+}
+
+/// HTML 属性值转义
+fn escape_attr(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+pub fn to_web_path(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn mime_type(path: &str) -> &'static str {
+    match path.rsplit('.').next().unwrap_or("").to_lowercase().as_str() {
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "png" => "image/png",
+        "gif" => "image/gif",
+        _ => "image/jpeg",
+    }
+}
+
+/// 写入目标目录，返回生成的文件路径
+pub fn write_conversions(dir: &Path, kind: EmitKind, conversions: &[Conversion]) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let mut sorted: Vec<&Conversion> = conversions.iter().collect();
+    sorted.sort_by(|a, b| a.original.cmp(&b.original));
+
+    let (path, content) = match kind {
+        EmitKind::Picture => {
+            let mut html = String::new();
+            for conversion in sorted {
+                html.push_str("<picture>\n");
+                html.push_str(&format!(
+                    "  <source srcset=\"{}\" type=\"{}\">\n",
+                    escape_attr(&conversion.converted),
+                    mime_type(&conversion.converted)
+                ));
+                html.push_str(&format!("  <img src=\"{}\" alt=\"\">\n", escape_attr(&conversion.original_href)));
+                html.push_str("</picture>\n");
+            }
+            (dir.join("picture.html"), html)
+        }
+        EmitKind::Json => {
+            let map: BTreeMap<&str, &str> = sorted.iter().map(|c| (c.original.as_str(), c.converted.as_str())).collect();
+            (dir.join("conversions.json"), serde_json::to_string_pretty(&map)? + "\n")
+        }
+    };
+
+    fs::write(&path, content)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_attribute_values() {
+        assert_eq!(escape_attr(r#"a "b" & <c>'s.webp"#), "a &quot;b&quot; &amp; &lt;c&gt;&#39;s.webp");
+    }
+
+    #[test]
+    fn original_href_is_relative_to_output_dir() {
+        let conversion = Conversion::new(Path::new("/site/images"), Path::new("sub/a.jpg"), Path::new("/site/dist"), Path::new("sub/a.webp"));
+        assert_eq!(conversion.original, "sub/a.jpg");
+        assert_eq!(conversion.original_href, "../images/sub/a.jpg");
+        assert_eq!(conversion.converted, "sub/a.webp");
+    }
+}
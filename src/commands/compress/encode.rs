@@ -0,0 +1,163 @@
+use clap::ValueEnum;
+use image::codecs::gif::GifEncoder;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::{CompressionType, FilterType, PngEncoder};
+use image::{DynamicImage, ImageFormat};
+
+/// PNG 的 deflate 压缩级别；image 默认使用 Fast，重新编码后的文件往往比原图更大
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PngCompression {
+    Fast,
+    Default,
+    Best,
+}
+
+/// PNG 逐行过滤策略，adaptive 对每行选择效果最好的过滤器
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum PngFilter {
+    None,
+    Sub,
+    Up,
+    Avg,
+    Paeth,
+    Adaptive,
+}
+
+/// `--format` 指定的输出格式，目标文件的扩展名随之改写
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Webp,
+    Jpeg,
+    Png,
+    Avif,
+}
+
+/// AVIF 编码速度 1-10；rav1e 未启用汇编优化，取偏快的档位
+const AVIF_SPEED: u8 = 8;
+
+/// 编码参数，与缩放无关
+#[derive(Debug, Clone, Copy)]
+pub struct EncodeSettings {
+    /// JPEG / WebP / AVIF 质量 1-100
+    pub quality: u8,
+    /// WebP 使用无损编码，忽略 quality
+    pub lossless: bool,
+    pub png_compression: PngCompression,
+    pub png_filter: PngFilter,
+}
+
+impl OutputFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Webp => "webp",
+            OutputFormat::Jpeg => "jpg",
+            OutputFormat::Png => "png",
+            OutputFormat::Avif => "avif",
+        }
+    }
+}
+
+impl PngCompression {
+    fn to_image(self) -> CompressionType {
+        match self {
+            PngCompression::Fast => CompressionType::Fast,
+            PngCompression::Default => CompressionType::Default,
+            PngCompression::Best => CompressionType::Best,
+        }
+    }
+}
+
+impl PngFilter {
+    fn to_image(self) -> FilterType {
+        match self {
+            PngFilter::None => FilterType::NoFilter,
+            PngFilter::Sub => FilterType::Sub,
+            PngFilter::Up => FilterType::Up,
+            PngFilter::Avg => FilterType::Avg,
+            PngFilter::Paeth => FilterType::Paeth,
+            PngFilter::Adaptive => FilterType::Adaptive,
+        }
+    }
+}
+
+/// 支持重新编码的输出格式，其余格式由调用方原样复制
+pub fn is_encodable(format: ImageFormat) -> bool {
+    matches!(
+        format,
+        ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::Gif | ImageFormat::WebP | ImageFormat::Avif
+    )
+}
+
+/// 质量参数是否影响该格式的输出；PNG、GIF 和无损 WebP 只能靠缩小尺寸减小体积
+pub fn uses_quality(format: ImageFormat, settings: &EncodeSettings) -> bool {
+    match format {
+        ImageFormat::Jpeg | ImageFormat::Avif => true,
+        ImageFormat::WebP => !settings.lossless,
+        _ => false,
+    }
+}
+
+/// 编码到内存，便于写入前比较大小
+pub fn encode(img: &DynamicImage, format: ImageFormat, settings: &EncodeSettings) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut buffer = Vec::new();
+
+    match format {
+        ImageFormat::Jpeg => {
+            // JPEG 不支持透明通道
+            let rgb = if img.color().has_alpha() { DynamicImage::ImageRgb8(img.to_rgb8()) } else { img.clone() };
+            rgb.write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, settings.quality))?;
+        }
+        ImageFormat::Png => {
+            img.write_with_encoder(PngEncoder::new_with_quality(
+                &mut buffer,
+                settings.png_compression.to_image(),
+                settings.png_filter.to_image(),
+            ))?;
+        }
+        ImageFormat::Gif => {
+            let mut encoder = GifEncoder::new(&mut buffer);
+            encoder.encode_frame(image::Frame::new(img.to_rgba8()))?;
+        }
+        ImageFormat::WebP => {
+            // libwebp 只接受 8 位 RGB/RGBA
+            let (width, height) = (img.width(), img.height());
+            let memory = if img.color().has_alpha() {
+                let rgba = img.to_rgba8();
+                encode_webp(webp::Encoder::from_rgba(&rgba, width, height), settings)
+            } else {
+                let rgb = img.to_rgb8();
+                encode_webp(webp::Encoder::from_rgb(&rgb, width, height), settings)
+            };
+            buffer.extend_from_slice(&memory);
+        }
+        ImageFormat::Avif => {
+            let encoder = ravif::Encoder::new()
+                .with_quality(settings.quality as f32)
+                .with_speed(AVIF_SPEED);
+            let (width, height) = (img.width() as usize, img.height() as usize);
+            let encoded = if img.color().has_alpha() {
+                let pixels: Vec<ravif::RGBA8> = img
+                    .to_rgba8()
+                    .pixels()
+                    .map(|p| ravif::RGBA8::new(p[0], p[1], p[2], p[3]))
+                    .collect();
+                encoder.encode_rgba(ravif::Img::new(pixels.as_slice(), width, height))?
+            } else {
+                let pixels: Vec<ravif::RGB8> = img.to_rgb8().pixels().map(|p| ravif::RGB8::new(p[0], p[1], p[2])).collect();
+                encoder.encode_rgb(ravif::Img::new(pixels.as_slice(), width, height))?
+            };
+            buffer = encoded.avif_file;
+        }
+        _ => return Err(t!("compress.unsupported_format_error", format = format!("{:?}", format)).into()),
+    }
+
+    Ok(buffer)
+}
+
+fn encode_webp(encoder: webp::Encoder, settings: &EncodeSettings) -> webp::WebPMemory {
+    if settings.lossless {
+        encoder.encode_lossless()
+    } else {
+        encoder.encode(settings.quality as f32)
+    }
+}
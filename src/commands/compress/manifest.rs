@@ -0,0 +1,88 @@
+use super::CompressOptions;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// 增量压缩的清单，保存在目标目录中
+pub const MANIFEST_FILE: &str = ".cyber-zen-compress.json";
+
+const MANIFEST_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    /// 键为相对源目录的路径，使用 `/` 分隔
+    pub files: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// 源文件内容的 SHA-256
+    pub hash: String,
+    /// 影响输出的参数的指纹
+    pub options: String,
+    /// 相对目标目录的输出路径
+    pub output: String,
+    pub size: u64,
+}
+
+impl Default for Manifest {
+    fn default() -> Self {
+        Manifest { version: MANIFEST_VERSION, files: BTreeMap::new() }
+    }
+}
+
+impl Manifest {
+    /// 清单不存在或版本不同时视为首次运行
+    pub fn load(dir: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let path = dir.join(MANIFEST_FILE);
+        if !path.exists() {
+            return Ok(Manifest::default());
+        }
+
+        let content = fs::read_to_string(&path)?;
+        let manifest: Manifest = serde_json::from_str(&content)
+            .map_err(|e| t!("common.parse_failed", file = path.display(), error = e))?;
+        if manifest.version != MANIFEST_VERSION {
+            return Ok(Manifest::default());
+        }
+        Ok(manifest)
+    }
+
+    pub fn save(&self, dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(dir.join(MANIFEST_FILE), serde_json::to_string_pretty(self)? + "\n")?;
+        Ok(())
+    }
+
+    /// 源文件和参数都未变化，且上次的输出仍在时可以跳过
+    pub fn unchanged(&self, key: &str, hash: &str, options: &str, dist_dir: &Path) -> Option<&ManifestEntry> {
+        let entry = self.files.get(key)?;
+        if entry.hash != hash || entry.options != options {
+            return None;
+        }
+        let size = fs::metadata(dist_dir.join(&entry.output)).ok()?.len();
+        (size == entry.size).then_some(entry)
+    }
+}
+
+pub fn hash_bytes(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// 影响输出内容的参数；源路径、目标路径和线程数不影响输出，不计入
+pub fn fingerprint(options: &CompressOptions) -> String {
+    let description = format!(
+        "{:?}|{:?}|{:?}|{:?}|{}|{:?}|{}|{}",
+        options.encode,
+        options.resize,
+        options.format,
+        options.max_bytes,
+        options.min_quality,
+        options.metadata,
+        options.min_savings,
+        options.allow_larger
+    );
+    hash_bytes(description.as_bytes())
+}
@@ -0,0 +1,228 @@
+use clap::ValueEnum;
+use exif::{In, Tag};
+use image::{DynamicImage, ImageFormat};
+use img_parts::{Bytes, DynImage, ImageEXIF, ImageICC};
+use std::io::Cursor;
+
+/// 输出文件保留哪些元数据；方向信息总是先应用到像素上
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MetadataPolicy {
+    /// 全部去掉；带非 sRGB 色彩配置的图片转换为 sRGB
+    Strip,
+    /// 保留 EXIF（含 GPS）和 ICC 色彩配置
+    Keep,
+    /// 只保留 EXIF 中的作者和版权，以及 ICC 色彩配置
+    KeepCopyright,
+    /// 只保留 ICC 色彩配置
+    KeepIcc,
+}
+
+/// EXIF 方向标签的值，1 表示无需旋转
+const ORIENTATION_NORMAL: u16 = 1;
+
+/// 从源文件读取的元数据
+pub struct SourceMetadata {
+    orientation: u16,
+    /// 不含 `Exif\0\0` 前缀的 TIFF 数据
+    exif: Option<Bytes>,
+    icc: Option<Bytes>,
+}
+
+impl SourceMetadata {
+    /// 读取 JPEG、PNG、WebP 中的 EXIF 和 ICC；其他格式或解析失败时视为没有元数据
+    pub fn read(data: &[u8]) -> Self {
+        let parts = DynImage::from_bytes(Bytes::copy_from_slice(data)).ok().flatten();
+        let exif = parts.as_ref().and_then(|p| p.exif());
+        let icc = parts.as_ref().and_then(|p| p.icc_profile());
+
+        let orientation = exif
+            .as_ref()
+            .and_then(|raw| exif::Reader::new().read_raw(raw.to_vec()).ok())
+            .and_then(|parsed| parsed.get_field(Tag::Orientation, In::PRIMARY)?.value.get_uint(0))
+            .map(|v| v as u16)
+            .unwrap_or(ORIENTATION_NORMAL);
+
+        SourceMetadata { orientation, exif, icc }
+    }
+
+    /// 按 EXIF 方向旋转、翻转像素，使输出不依赖查看器是否支持方向标签
+    pub fn orient(&self, img: DynamicImage) -> DynamicImage {
+        match self.orientation {
+            2 => img.fliph(),
+            3 => img.rotate180(),
+            4 => img.flipv(),
+            5 => img.rotate90().fliph(),
+            6 => img.rotate90(),
+            7 => img.rotate270().fliph(),
+            8 => img.rotate270(),
+            _ => img,
+        }
+    }
+
+    /// 输出中无法携带色彩配置时，需要先把像素转换到 sRGB
+    pub fn needs_srgb(&self, policy: MetadataPolicy, format: ImageFormat) -> bool {
+        let icc_kept = policy != MetadataPolicy::Strip && supports_metadata(format);
+        self.icc.is_some() && !icc_kept
+    }
+
+    /// 用源文件的 ICC 色彩配置把像素转换到 sRGB；配置无法解析或本身就是 sRGB 时原样返回
+    pub fn convert_to_srgb(&self, img: DynamicImage) -> DynamicImage {
+        let Some(profile) = self.icc.as_ref().and_then(|icc| qcms::Profile::new_from_slice(icc, false)) else {
+            return img;
+        };
+        if profile.is_sRGB() {
+            return img;
+        }
+
+        let srgb = qcms::Profile::new_sRGB();
+        if img.color().has_alpha() {
+            let mut rgba = img.to_rgba8();
+            match qcms::Transform::new(&profile, &srgb, qcms::DataType::RGBA8, qcms::Intent::default()) {
+                Some(transform) => transform.apply(&mut rgba),
+                None => return img,
+            }
+            DynamicImage::ImageRgba8(rgba)
+        } else {
+            let mut rgb = img.to_rgb8();
+            match qcms::Transform::new(&profile, &srgb, qcms::DataType::RGB8, qcms::Intent::default()) {
+                Some(transform) => transform.apply(&mut rgb),
+                None => return img,
+            }
+            DynamicImage::ImageRgb8(rgb)
+        }
+    }
+
+    /// 按策略整理要写入的 EXIF 和 ICC
+    fn select(&self, policy: MetadataPolicy) -> (Option<Bytes>, Option<Bytes>) {
+        match policy {
+            MetadataPolicy::Strip => (None, None),
+            MetadataPolicy::KeepIcc => (None, self.icc.clone()),
+            MetadataPolicy::KeepCopyright => (self.exif.as_ref().and_then(|raw| copyright_only(raw)), self.icc.clone()),
+            MetadataPolicy::Keep => {
+                // 像素已经按方向旋转过，方向标签必须重置，否则查看器会再转一次
+                let exif = self.exif.as_ref().map(|raw| {
+                    let mut raw = raw.to_vec();
+                    reset_orientation(&mut raw);
+                    Bytes::from(raw)
+                });
+                (exif, self.icc.clone())
+            }
+        }
+    }
+
+    /// 原文件已符合策略，可以原样保留而不泄露应去掉的元数据
+    fn allows_original(&self, policy: MetadataPolicy) -> bool {
+        match policy {
+            MetadataPolicy::Keep => true,
+            MetadataPolicy::KeepCopyright | MetadataPolicy::KeepIcc => self.exif.is_none(),
+            MetadataPolicy::Strip => self.exif.is_none() && self.icc.is_none(),
+        }
+    }
+
+    /// 可以代替重新编码结果的原文件：已符合策略时原样返回，只有 EXIF 不符合时无损替换 EXIF 段；
+    /// 带方向标签（去掉后朝向会变）或需要去掉色彩配置（去掉后颜色会变）时返回 None
+    pub fn original_for(&self, data: &[u8], policy: MetadataPolicy) -> Option<Vec<u8>> {
+        if self.allows_original(policy) {
+            return Some(data.to_vec());
+        }
+        if self.orientation != ORIENTATION_NORMAL || (policy == MetadataPolicy::Strip && self.icc.is_some()) {
+            return None;
+        }
+
+        let mut parts = DynImage::from_bytes(Bytes::copy_from_slice(data)).ok()??;
+        parts.set_exif(self.select(policy).0);
+        let mut output = Vec::new();
+        parts.encoder().write_to(&mut output).ok()?;
+        Some(output)
+    }
+
+    /// 写入元数据后增加的大约字节数，用于 --max-bytes 预留空间
+    pub fn overhead(&self, policy: MetadataPolicy, format: ImageFormat) -> u64 {
+        if !supports_metadata(format) {
+            return 0;
+        }
+        let (exif, icc) = self.select(policy);
+        // 每段另有段头或块头
+        exif.map_or(0, |e| e.len() as u64 + 16) + icc.map_or(0, |i| i.len() as u64 + 32)
+    }
+
+    /// 把元数据写入编码后的文件；格式不支持时原样返回
+    pub fn embed(&self, encoded: Vec<u8>, policy: MetadataPolicy, format: ImageFormat) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        let (exif, icc) = self.select(policy);
+        if !supports_metadata(format) || (exif.is_none() && icc.is_none()) {
+            return Ok(encoded);
+        }
+        let Some(mut parts) = DynImage::from_bytes(Bytes::from(encoded.clone()))? else {
+            return Ok(encoded);
+        };
+
+        parts.set_exif(exif);
+        parts.set_icc_profile(icc);
+
+        let mut output = Vec::new();
+        parts.encoder().write_to(&mut output)?;
+        Ok(output)
+    }
+}
+
+/// img-parts 能写入元数据的格式
+fn supports_metadata(format: ImageFormat) -> bool {
+    matches!(format, ImageFormat::Jpeg | ImageFormat::Png | ImageFormat::WebP)
+}
+
+/// 重新生成只含作者和版权的 EXIF
+fn copyright_only(raw: &[u8]) -> Option<Bytes> {
+    let parsed = exif::Reader::new().read_raw(raw.to_vec()).ok()?;
+    let fields: Vec<&exif::Field> = [Tag::Artist, Tag::Copyright]
+        .into_iter()
+        .filter_map(|tag| parsed.get_field(tag, In::PRIMARY))
+        .collect();
+    if fields.is_empty() {
+        return None;
+    }
+
+    let mut writer = exif::experimental::Writer::new();
+    for field in fields {
+        writer.push_field(field);
+    }
+    let mut output = Cursor::new(Vec::new());
+    writer.write(&mut output, parsed.little_endian()).ok()?;
+    Some(Bytes::from(output.into_inner()))
+}
+
+/// 直接修改 TIFF 数据中 IFD0 的方向标签，保留其余字段的原始字节
+fn reset_orientation(tiff: &mut [u8]) {
+    let little_endian = match tiff.get(..2) {
+        Some(b"II") => true,
+        Some(b"MM") => false,
+        _ => return,
+    };
+    let read_u16 = |data: &[u8], at: usize| -> Option<u16> {
+        let bytes = [*data.get(at)?, *data.get(at + 1)?];
+        Some(if little_endian { u16::from_le_bytes(bytes) } else { u16::from_be_bytes(bytes) })
+    };
+    let read_u32 = |data: &[u8], at: usize| -> Option<u32> {
+        let bytes = [*data.get(at)?, *data.get(at + 1)?, *data.get(at + 2)?, *data.get(at + 3)?];
+        Some(if little_endian { u32::from_le_bytes(bytes) } else { u32::from_be_bytes(bytes) })
+    };
+
+    let Some(ifd) = read_u32(tiff, 4).map(|v| v as usize) else {
+        return;
+    };
+    let Some(count) = read_u16(tiff, ifd) else {
+        return;
+    };
+
+    for i in 0..count as usize {
+        let entry = ifd + 2 + i * 12;
+        if read_u16(tiff, entry) != Some(Tag::Orientation.number()) {
+            continue;
+        }
+        // SHORT 类型、数量为 1，值直接存放在条目的值字段中
+        let value = if little_endian { ORIENTATION_NORMAL.to_le_bytes() } else { ORIENTATION_NORMAL.to_be_bytes() };
+        if let Some(slot) = tiff.get_mut(entry + 8..entry + 10) {
+            slot.copy_from_slice(&value);
+        }
+        return;
+    }
+}
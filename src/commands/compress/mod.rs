@@ -0,0 +1,375 @@
+mod batch;
+mod budget;
+mod emit;
+mod encode;
+mod manifest;
+mod metadata;
+mod resize;
+
+use colored::*;
+use crate::config::load_settings;
+use image::ImageFormat;
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub use budget::parse_size;
+pub use emit::EmitKind;
+pub use encode::{EncodeSettings, OutputFormat, PngCompression, PngFilter};
+pub use metadata::MetadataPolicy;
+pub use resize::{Fit, Gravity, ResizeSettings};
+
+use emit::Conversion;
+use metadata::SourceMetadata;
+
+pub struct CompressOptions {
+    pub src: String,
+    pub dist: Option<String>,
+    /// 未指定任何尺寸参数时保持原尺寸，只重新编码
+    pub resize: ResizeSettings,
+    pub encode: EncodeSettings,
+    /// 转换为指定格式并改写扩展名，未指定时保持原格式
+    pub format: Option<OutputFormat>,
+    /// 转换后输出 `<picture>` 片段或 JSON 映射
+    pub emit: Option<EmitKind>,
+    /// 单个文件的体积上限，超出时降低质量、缩小尺寸
+    pub max_bytes: Option<u64>,
+    /// 按体积上限搜索质量时的最低质量
+    pub min_quality: u8,
+    /// settings.toml 中 [compress.presets.<名称>] 定义的缩放预设
+    pub preset: Option<String>,
+    /// 处理目录时的并行线程数，默认按 CPU 核数
+    pub jobs: Option<usize>,
+    pub metadata: MetadataPolicy,
+    /// 节省比例（百分比）低于此值时保留原文件
+    pub min_savings: f64,
+    /// 即使输出比原文件大也写入输出
+    pub allow_larger: bool,
+    /// 直接写入 --dist，按清单跳过未变化的图片并清理源文件已删除的输出
+    pub incremental: bool,
+}
+
+/// 单个文件的处理结果
+struct Outcome {
+    /// 实际写入的路径
+    written: PathBuf,
+    original_size: u64,
+    new_size: u64,
+    /// 原始尺寸和输出尺寸；原样复制时为 None
+    dimensions: Option<((u32, u32), (u32, u32))>,
+    /// --max-bytes 选用的质量
+    chosen_quality: Option<String>,
+    /// 原样复制的原因
+    copied: Option<String>,
+    /// 压缩效果不足，保留了原文件
+    kept: bool,
+    /// 增量模式下源文件和参数均未变化，沿用上次的输出
+    skipped: bool,
+}
+
+pub fn run_compress(mut options: CompressOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(name) = &options.preset {
+        let presets = load_settings(Path::new("."))?.compress.presets;
+        let preset = presets.get(name).ok_or_else(|| {
+            let available: Vec<&str> = presets.keys().map(String::as_str).collect();
+            t!("compress.unknown_preset", name = name, available = available.join(", "))
+        })?;
+        options.resize.apply_preset(preset);
+    }
+
+    let CompressOptions { src, dist, resize, .. } = &options;
+
+    println!("{}", t!("compress.start").green());
+    println!("{} {}", t!("compress.src").cyan(), src);
+    println!("{} {}", t!("compress.dist").cyan(), dist.clone().unwrap_or_else(|| t!("compress.unspecified")));
+    println!("{} {}", t!("compress.quality").cyan(), options.encode.quality);
+    if let Some(max_bytes) = options.max_bytes {
+        println!("{} {} bytes", t!("compress.max_bytes").cyan(), max_bytes);
+        if options.min_quality > options.encode.quality {
+            return Err(t!("compress.invalid_min_quality", min_quality = options.min_quality, quality = options.encode.quality).into());
+        }
+    }
+    if let Some(format) = options.format {
+        println!("{} {}", t!("compress.format").cyan(), format.extension());
+    }
+    if let Some(scale) = resize.scale {
+        println!("{} {:.2}", t!("compress.scale").cyan(), scale);
+    }
+    if resize.max_width.is_some() || resize.max_height.is_some() {
+        let limit = |value: Option<u32>| value.map(|v| v.to_string()).unwrap_or_else(|| "-".to_string());
+        println!(
+            "{} {}x{} ({})",
+            t!("compress.max_dimensions").cyan(),
+            limit(resize.max_width),
+            limit(resize.max_height),
+            format!("{:?}", resize.fit).to_lowercase()
+        );
+    }
+    resize.validate()?;
+    if !(0.0..=100.0).contains(&options.min_savings) {
+        return Err(t!("compress.invalid_min_savings").into());
+    }
+
+    let src_abs = fs::canonicalize(src)?;
+
+    if !src_abs.exists() {
+        return Err(t!("compress.src_missing", path = src_abs.display()).into());
+    }
+
+    // 增量模式需要固定的目标目录，不能每次生成新的时间戳目录
+    if options.incremental {
+        let Some(dist) = dist else {
+            return Err(t!("compress.incremental_needs_dist").into());
+        };
+        if !src_abs.is_dir() {
+            return Err(t!("compress.incremental_needs_dir").into());
+        }
+        return batch::compress_directory(&src_abs, Path::new(dist), &options);
+    }
+
+    let dist_abs = if let Some(d) = dist {
+        fs::canonicalize(d).unwrap_or_else(|_| PathBuf::from(d))
+    } else {
+        let timestamp = get_timestamp();
+        PathBuf::from(format!("compressed_{}", timestamp))
+    };
+
+    let dist_with_timestamp = if contains_timestamp(&dist_abs) {
+        dist_abs.clone()
+    } else {
+        let timestamp = get_timestamp();
+        add_timestamp_to_path(&dist_abs, &timestamp)
+    };
+
+    if let Some(parent) = dist_with_timestamp.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let metadata = fs::metadata(&src_abs)?;
+    if metadata.is_dir() {
+        batch::compress_directory(&src_abs, &dist_with_timestamp, &options)
+    } else {
+        let original_ext = src_abs.extension().and_then(|s| s.to_str()).unwrap_or("");
+        let original_name = src_abs.file_stem().and_then(|s| s.to_str()).unwrap_or("file");
+        
+        let final_dist = if dist_abs == dist_with_timestamp && dist_with_timestamp.extension().is_none() {
+            let timestamp = get_timestamp();
+            dist_with_timestamp.join(format!("{}_{}.{}", original_name, timestamp, original_ext))
+        } else {
+            dist_with_timestamp
+        };
+        let final_dist = converted_path(&final_dist, options.format);
+
+        if let Some(parent) = final_dist.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let written = compress_file(&src_abs, &final_dist, &options)?;
+        if let (Some(kind), Some(dir), Some(src_dir), Some(original), Some(converted)) =
+            (options.emit, written.parent(), src_abs.parent(), src_abs.file_name(), written.file_name())
+        {
+            let conversions = [Conversion::new(src_dir, Path::new(original), dir, Path::new(converted))];
+            let path = emit::write_conversions(dir, kind, &conversions)?;
+            println!("{} {}", t!("compress.emitted").green(), path.display());
+        }
+        Ok(())
+    }
+}
+
+/// 指定了 --format 时改写扩展名
+fn converted_path(path: &Path, format: Option<OutputFormat>) -> PathBuf {
+    match format {
+        Some(format) => path.with_extension(format.extension()),
+        None => path.to_path_buf(),
+    }
+}
+
+fn get_timestamp() -> String {
+    let datetime = chrono::Local::now();
+    datetime.format("%Y%m%d_%H%M%S").to_string()
+}
+
+fn add_timestamp_to_path(path: &Path, timestamp: &str) -> PathBuf {
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let base = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    
+    if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+        let name = path.file_stem().and_then(|s| s.to_str()).unwrap_or("");
+        dir.join(format!("{}_{}.{}", name, timestamp, ext))
+    } else {
+        dir.join(format!("{}_{}", base, timestamp))
+    }
+}
+
+fn contains_timestamp(path: &Path) -> bool {
+    let re = Regex::new(r"_\d{8}_\d{6}$").unwrap();
+    if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+        re.is_match(name)
+    } else {
+        false
+    }
+}
+
+fn compress_file(src_file: &Path, dist_file: &Path, options: &CompressOptions) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    println!("{} {}", t!("compress.file").yellow(), src_file.file_name().unwrap_or_default().to_string_lossy());
+    
+    if !is_image_file(src_file) {
+        return Err(t!("compress.unsupported_file", ext = format!("{:?}", src_file.extension())).into());
+    }
+
+    let outcome = compress_image_file(src_file, dist_file, options)?;
+    let name = src_file.file_name().unwrap_or_default().to_string_lossy();
+
+    match (&outcome.copied, outcome.dimensions) {
+        (Some(reason), _) => {
+            println!("{} {}", reason.yellow(), t!("compress.copy_instead", name = name));
+            println!("{} {}", t!("compress.copied").green(), name);
+            println!("  {} {} bytes", t!("compress.file_size").cyan(), outcome.new_size);
+        }
+        (None, Some(((original_width, original_height), (new_width, new_height)))) => {
+            if let Some(quality) = &outcome.chosen_quality {
+                println!("  {} {}", t!("compress.chosen_quality").cyan(), quality);
+            }
+            if outcome.kept {
+                println!("{} {}", t!("compress.kept_original").yellow(), name);
+            } else {
+                println!("{} {}", t!("compress.done").green(), name);
+            }
+            println!("  {} {}x{}", t!("compress.original_dimensions").cyan(), original_width, original_height);
+            println!("  {} {}x{}", t!("compress.new_dimensions").cyan(), new_width, new_height);
+            println!("  {} {} bytes", t!("compress.original_size").cyan(), outcome.original_size);
+            println!("  {} {} bytes", t!("compress.new_size").cyan(), outcome.new_size);
+            println!("  {} {:.2}%", t!("compress.rate").cyan(), ratio(&outcome) * 100.0);
+        }
+        (None, None) => {}
+    }
+
+    println!("{} {}", t!("compress.file_done").green(), outcome.written.display());
+    Ok(outcome.written)
+}
+
+/// 输出大小与原始大小之比
+fn ratio(outcome: &Outcome) -> f64 {
+    outcome.new_size as f64 / outcome.original_size.max(1) as f64
+}
+
+/// 按 1024 进位显示体积
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+fn is_image_file(path: &Path) -> bool {
+    if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+        let ext_lower = ext.to_lowercase();
+        matches!(ext_lower.as_str(), "jpg" | "jpeg" | "png" | "gif" | "bmp" | "webp")
+    } else {
+        false
+    }
+}
+
+/// 不输出任何内容，便于在并行处理时调用；原样复制时保留源文件的扩展名
+fn compress_image_file(src_file: &Path, dist_file: &Path, options: &CompressOptions) -> Result<Outcome, Box<dyn std::error::Error>> {
+    let src_data = fs::read(src_file)?;
+    let copy = |reason: String| -> Result<Outcome, Box<dyn std::error::Error>> {
+        let dist_file = copy_path(src_file, dist_file);
+        fs::write(&dist_file, &src_data)?;
+        Ok(Outcome {
+            written: dist_file,
+            original_size: src_data.len() as u64,
+            new_size: src_data.len() as u64,
+            dimensions: None,
+            chosen_quality: None,
+            copied: Some(reason),
+            kept: false,
+            skipped: false,
+        })
+    };
+
+    let Ok(img) = image::load_from_memory(&src_data) else {
+        return copy(t!("compress.undecodable"));
+    };
+
+    let metadata = SourceMetadata::read(&src_data);
+    let img = metadata.orient(img);
+    let original_dimensions = (img.width(), img.height());
+
+    let format = image::ImageFormat::from_path(dist_file).unwrap_or(ImageFormat::Png);
+    if !encode::is_encodable(format) {
+        return copy(format!("{} {:?}", t!("compress.unsupported_format"), format));
+    }
+
+    // 输出不带色彩配置时，不转换会导致颜色偏移
+    let img = if metadata.needs_srgb(options.metadata, format) {
+        metadata.convert_to_srgb(img)
+    } else {
+        img
+    };
+
+    let img = resize::apply(img, &options.resize);
+
+    let (encoded, new_dimensions, chosen_quality) = match options.max_bytes {
+        Some(max_bytes) => {
+            let max_bytes = max_bytes.saturating_sub(metadata.overhead(options.metadata, format));
+            let fitted = budget::fit_to_budget(&img, format, &options.encode, &options.resize, max_bytes, options.min_quality)?;
+            let quality = match fitted.quality {
+                Some(quality) => quality.to_string(),
+                None => t!("compress.lossless"),
+            };
+            (fitted.data, (fitted.width, fitted.height), Some(quality))
+        }
+        None => (encode::encode(&img, format, &options.encode)?, (img.width(), img.height()), None),
+    };
+    let encoded = metadata.embed(encoded, options.metadata, format)?;
+
+    // 原文件满足所有要求且重新编码收益不足时保留原文件；EXIF 不符合策略时先无损整理再比较
+    // 未设置 --min-savings 时，只要不比原文件小就保留原文件
+    let original = (!options.allow_larger
+        && new_dimensions == original_dimensions
+        && image::guess_format(&src_data).ok() == Some(format))
+    .then(|| metadata.original_for(&src_data, options.metadata))
+    .flatten()
+    .filter(|original| options.max_bytes.is_none_or(|max_bytes| original.len() as u64 <= max_bytes));
+    let keep_original = original.as_ref().is_some_and(|original| {
+        let savings = (1.0 - encoded.len() as f64 / original.len().max(1) as f64) * 100.0;
+        encoded.len() >= original.len() || savings < options.min_savings
+    });
+    let output = match &original {
+        Some(original) if keep_original => original,
+        _ => &encoded,
+    };
+    fs::write(dist_file, output)?;
+
+    Ok(Outcome {
+        written: dist_file.to_path_buf(),
+        original_size: src_data.len() as u64,
+        new_size: output.len() as u64,
+        dimensions: Some((original_dimensions, new_dimensions)),
+        chosen_quality,
+        copied: None,
+        kept: keep_original,
+        skipped: false,
+    })
+}
+
+/// 原样复制的文件内容仍是源格式，扩展名不能随 --format 改写；重名时改成的 a.png.webp 还原为 a.png
+fn copy_path(src_file: &Path, dist_file: &Path) -> PathBuf {
+    match src_file.extension() {
+        Some(ext) if dist_file.file_stem().and_then(|stem| Path::new(stem).extension()) == Some(ext) => {
+            dist_file.with_extension("")
+        }
+        Some(ext) => dist_file.with_extension(ext),
+        None => dist_file.to_path_buf(),
+    }
+}
+
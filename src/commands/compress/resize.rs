@@ -0,0 +1,234 @@
+use crate::config::{CompressPreset, ResampleFilter, SharpenSettings};
+use clap::ValueEnum;
+use image::{imageops, DynamicImage, GenericImageView, ImageBuffer, Rgba, RgbaImage};
+
+/// 指定 --max-width / --max-height 时的适配方式，均不会放大图片
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Fit {
+    /// 等比缩小到框内，输出尺寸随图片而定
+    Inside,
+    /// 等比缩小到框内，并填充透明（JPEG 为白色）边距到框的尺寸
+    Contain,
+    /// 等比缩小到刚好覆盖框，超出部分按 --gravity 裁掉
+    Cover,
+    /// 拉伸到框的尺寸，不保持宽高比
+    Fill,
+}
+
+/// cover 裁剪时保留的区域
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Gravity {
+    Center,
+    North,
+    South,
+    East,
+    West,
+    Northeast,
+    Northwest,
+    Southeast,
+    Southwest,
+}
+
+/// 尺寸参数；`scale` 与最大宽高互斥
+#[derive(Debug, Clone, Copy)]
+pub struct ResizeSettings {
+    pub scale: Option<f64>,
+    pub max_width: Option<u32>,
+    pub max_height: Option<u32>,
+    pub fit: Fit,
+    pub gravity: Gravity,
+    /// 未指定时取预设，再默认为 lanczos3
+    pub filter: Option<ResampleFilter>,
+    /// 在线性光空间缩小；未指定时取预设，再默认开启
+    pub linear: Option<bool>,
+    pub sharpen: Option<SharpenSettings>,
+}
+
+impl ResizeSettings {
+    /// 除 inside 外的适配方式需要完整的框
+    pub fn validate(&self) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(scale) = self.scale {
+            if !(0.1..=1.0).contains(&scale) {
+                return Err(t!("compress.invalid_scale").into());
+            }
+        }
+        let has_box = self.max_width.is_some() && self.max_height.is_some();
+        if self.fit != Fit::Inside && !has_box {
+            return Err(t!("compress.fit_needs_box", fit = format!("{:?}", self.fit).to_lowercase()).into());
+        }
+        Ok(())
+    }
+
+    /// 用预设补全命令行未指定的项；--sharpen 只开启锐化，参数仍以预设为准
+    pub fn apply_preset(&mut self, preset: &CompressPreset) {
+        self.filter = self.filter.or(preset.filter);
+        self.linear = self.linear.or(preset.linear);
+        self.sharpen = preset.sharpen.or(self.sharpen);
+    }
+
+    fn filter(&self) -> ResampleFilter {
+        self.filter.unwrap_or_default()
+    }
+}
+
+/// 按尺寸参数调整图片；未指定任何尺寸参数时原样返回
+pub fn apply(img: DynamicImage, settings: &ResizeSettings) -> DynamicImage {
+    let (width, height) = img.dimensions();
+
+    if let Some(scale) = settings.scale {
+        let new_width = ((width as f64 * scale) as u32).max(1);
+        let new_height = ((height as f64 * scale) as u32).max(1);
+        return resize_image(&img, new_width, new_height, settings);
+    }
+
+    if settings.max_width.is_none() && settings.max_height.is_none() {
+        return img;
+    }
+
+    // 缺少的一边不限制
+    let box_width = settings.max_width.unwrap_or(u32::MAX);
+    let box_height = settings.max_height.unwrap_or(u32::MAX);
+    let ratio_width = box_width as f64 / width as f64;
+    let ratio_height = box_height as f64 / height as f64;
+
+    match settings.fit {
+        Fit::Inside => shrink(img, ratio_width.min(ratio_height), settings),
+        Fit::Contain => {
+            let img = shrink(img, ratio_width.min(ratio_height), settings);
+            pad(&img, box_width, box_height)
+        }
+        Fit::Cover => {
+            let img = shrink(img, ratio_width.max(ratio_height), settings);
+            crop(&img, box_width, box_height, settings.gravity)
+        }
+        Fit::Fill => {
+            let new_width = box_width.min(width);
+            let new_height = box_height.min(height);
+            if (new_width, new_height) == (width, height) {
+                img
+            } else {
+                resize_image(&img, new_width, new_height, settings)
+            }
+        }
+    }
+}
+
+/// 等比缩小，比例不小于 1 时保持原图
+fn shrink(img: DynamicImage, ratio: f64, settings: &ResizeSettings) -> DynamicImage {
+    if ratio >= 1.0 {
+        return img;
+    }
+    let new_width = ((img.width() as f64 * ratio).round() as u32).max(1);
+    let new_height = ((img.height() as f64 * ratio).round() as u32).max(1);
+    resize_image(&img, new_width, new_height, settings)
+}
+
+/// 居中放到框内；边距为透明白色，转为 JPEG 丢弃透明通道后即为白色
+fn pad(img: &DynamicImage, box_width: u32, box_height: u32) -> DynamicImage {
+    if img.dimensions() == (box_width, box_height) {
+        return img.clone();
+    }
+    let mut canvas = RgbaImage::from_pixel(box_width, box_height, Rgba([255, 255, 255, 0]));
+    let x = (box_width - img.width()) / 2;
+    let y = (box_height - img.height()) / 2;
+    imageops::replace(&mut canvas, &img.to_rgba8(), x as i64, y as i64);
+    DynamicImage::ImageRgba8(canvas)
+}
+
+/// 裁到框的尺寸；图片小于框的一边保持不变
+fn crop(img: &DynamicImage, box_width: u32, box_height: u32, gravity: Gravity) -> DynamicImage {
+    let (width, height) = img.dimensions();
+    let crop_width = box_width.min(width);
+    let crop_height = box_height.min(height);
+    if (crop_width, crop_height) == (width, height) {
+        return img.clone();
+    }
+
+    let (free_x, free_y) = (width - crop_width, height - crop_height);
+    let x = match gravity {
+        Gravity::West | Gravity::Northwest | Gravity::Southwest => 0,
+        Gravity::East | Gravity::Northeast | Gravity::Southeast => free_x,
+        _ => free_x / 2,
+    };
+    let y = match gravity {
+        Gravity::North | Gravity::Northwest | Gravity::Northeast => 0,
+        Gravity::South | Gravity::Southwest | Gravity::Southeast => free_y,
+        _ => free_y / 2,
+    };
+    img.crop_imm(x, y, crop_width, crop_height)
+}
+
+/// 缩放到指定尺寸；缩小较多时按设置锐化
+pub fn resize_image(img: &DynamicImage, width: u32, height: u32, settings: &ResizeSettings) -> DynamicImage {
+    let filter = settings.filter();
+    let image_filter = match filter {
+        ResampleFilter::Lanczos3 => imageops::FilterType::Lanczos3,
+        ResampleFilter::CatmullRom => imageops::FilterType::CatmullRom,
+        ResampleFilter::Triangle => imageops::FilterType::Triangle,
+        ResampleFilter::Nearest => imageops::FilterType::Nearest,
+    };
+
+    // nearest 不混合像素，无需转换到线性空间
+    let resized = if settings.linear.unwrap_or(true) && filter != ResampleFilter::Nearest {
+        resize_linear(img, width, height, image_filter)
+    } else {
+        img.resize_exact(width, height, image_filter)
+    };
+
+    let scale = (width as f64 / img.width() as f64).max(height as f64 / img.height() as f64);
+    match settings.sharpen {
+        Some(sharpen) if scale <= sharpen.below_scale => resized.unsharpen(sharpen.sigma, sharpen.threshold),
+        _ => resized,
+    }
+}
+
+/// 在 sRGB 空间直接插值会让缩小后的图片偏暗、细节发灰，因此先转为线性光并预乘透明度
+fn resize_linear(img: &DynamicImage, width: u32, height: u32, filter: imageops::FilterType) -> DynamicImage {
+    let to_linear: Vec<f32> = (0..=255u8).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+
+    let rgba = img.to_rgba8();
+    let linear: ImageBuffer<Rgba<f32>, Vec<f32>> = ImageBuffer::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let alpha = a as f32 / 255.0;
+        Rgba([
+            to_linear[r as usize] * alpha,
+            to_linear[g as usize] * alpha,
+            to_linear[b as usize] * alpha,
+            alpha,
+        ])
+    });
+
+    let resized = imageops::resize(&linear, width, height, filter);
+
+    let output = RgbaImage::from_fn(width, height, |x, y| {
+        let [r, g, b, a] = resized.get_pixel(x, y).0;
+        let alpha = a.clamp(0.0, 1.0);
+        let channel = |v: f32| {
+            let v = if alpha > 0.0 { v / alpha } else { 0.0 };
+            (linear_to_srgb(v.clamp(0.0, 1.0)) * 255.0).round() as u8
+        };
+        Rgba([channel(r), channel(g), channel(b), (alpha * 255.0).round() as u8])
+    });
+
+    if img.color().has_alpha() {
+        DynamicImage::ImageRgba8(output)
+    } else {
+        DynamicImage::ImageRgb8(DynamicImage::ImageRgba8(output).to_rgb8())
+    }
+}
+
+fn srgb_to_linear(v: f32) -> f32 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+fn linear_to_srgb(v: f32) -> f32 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
@@ -0,0 +1,93 @@
+use clap::ValueEnum;
+use crate::config::FileTypeManager;
+use crate::git::{check_git_repo, current_branch};
+use serde::Serialize;
+use std::path::Path;
+
+use super::{analyze_git_changes, display_changes, propose_commit, resolve_style, ChangeInfo, CommitProposal};
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Yaml,
+}
+
+/// `gcm --analyze` 的完整输出，供编辑器插件和机器人复用分类结果
+#[derive(Serialize)]
+struct AnalysisReport<'a> {
+    branch: String,
+    stats: ChangeStats,
+    changes: &'a [ChangeInfo],
+    proposal: Option<CommitProposal>,
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ChangeStats {
+    added: usize,
+    modified: usize,
+    deleted: usize,
+    renamed: usize,
+    total: usize,
+    additions: u32,
+    deletions: u32,
+}
+
+pub fn run_analyze(format: OutputFormat, style: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let file_type_manager = FileTypeManager::new()?;
+    let style = resolve_style(repo, &file_type_manager, style)?;
+    let changes = analyze_git_changes(repo, &file_type_manager)?;
+
+    let proposal = if changes.is_empty() {
+        None
+    } else {
+        Some(propose_commit(&changes, &file_type_manager, style))
+    };
+    let message = proposal
+        .as_ref()
+        .map(|p| p.render())
+        .unwrap_or_default();
+
+    match format {
+        OutputFormat::Text => {
+            display_changes(&changes);
+            println!("\n{}", message);
+        }
+        OutputFormat::Json | OutputFormat::Yaml => {
+            let report = AnalysisReport {
+                branch: current_branch(repo),
+                stats: collect_stats(&changes),
+                changes: &changes,
+                proposal,
+                message,
+            };
+
+            let output = if matches!(format, OutputFormat::Json) {
+                serde_json::to_string_pretty(&report)?
+            } else {
+                serde_yaml::to_string(&report)?
+            };
+            println!("{}", output.trim_end());
+        }
+    }
+
+    Ok(())
+}
+
+fn collect_stats(changes: &[ChangeInfo]) -> ChangeStats {
+    let count = |status: &str| changes.iter().filter(|c| c.status == status).count();
+
+    ChangeStats {
+        added: count("A"),
+        modified: count("M"),
+        deleted: count("D"),
+        renamed: count("R"),
+        total: changes.len(),
+        additions: changes.iter().filter_map(|c| c.additions).sum(),
+        deletions: changes.iter().filter_map(|c| c.deletions).sum(),
+    }
+}
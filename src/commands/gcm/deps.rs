@@ -0,0 +1,526 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::git::git_output;
+use crate::i18n::{fill, lookup, Lang};
+
+/// 依赖清单和锁文件；提交只涉及这些文件时生成 `chore(deps)` 摘要
+const MANIFEST_FILES: &[&str] = &["Cargo.toml", "package.json"];
+const LOCK_FILES: &[&str] = &["Cargo.lock", "package-lock.json", "npm-shrinkwrap.json", "pnpm-lock.yaml", "yarn.lock"];
+
+/// 摘要中最多列出的依赖数，其余只给出数量
+const SUMMARY_LIMIT: usize = 3;
+
+/// 锁文件中一个依赖的版本变化
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct DependencyChange {
+    name: String,
+    /// 旧版本，新增依赖为空
+    from: Option<String>,
+    /// 新版本，移除依赖为空
+    to: Option<String>,
+}
+
+impl DependencyChange {
+    /// 写入提交信息，`lang` 为提交信息语言
+    fn describe(&self, lang: Lang) -> String {
+        let name = ("name", self.name.clone());
+        match (&self.from, &self.to) {
+            (Some(from), Some(to)) => fill(lookup(lang, "deps.bump"), &[name, ("from", from.clone()), ("to", to.clone())]),
+            (None, Some(_)) => fill(lookup(lang, "deps.add"), &[name]),
+            (Some(_), None) => fill(lookup(lang, "deps.remove"), &[name]),
+            (None, None) => self.name.clone(),
+        }
+    }
+
+    /// 摘要中先列升级，再列新增和移除
+    fn order(&self) -> u8 {
+        match (&self.from, &self.to) {
+            (Some(_), Some(_)) => 0,
+            (None, Some(_)) => 1,
+            _ => 2,
+        }
+    }
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+pub(crate) fn is_dependency_file(path: &str) -> bool {
+    let name = file_name(path);
+    MANIFEST_FILES.contains(&name) || LOCK_FILES.contains(&name)
+}
+
+pub(crate) fn is_lock_file(path: &str) -> bool {
+    LOCK_FILES.contains(&file_name(path))
+}
+
+/// 读取某个版本中的文件内容，`rev` 为空字符串时读取暂存区
+pub(crate) fn read_revision(repo: &Path, rev: &str, path: &str) -> Option<String> {
+    git_output(repo, &["show", &format!("{}:{}", rev, path)]).ok()
+}
+
+pub(crate) fn read_worktree(repo: &Path, path: &str) -> Option<String> {
+    fs::read_to_string(repo.join(path)).ok()
+}
+
+/// 对比锁文件新旧内容中解析出的版本
+pub(crate) fn diff_lock_file(path: &str, old: Option<&str>, new: Option<&str>) -> Vec<DependencyChange> {
+    let parse = |content: Option<&str>| content.map(|c| parse_lock_file(path, c)).unwrap_or_default();
+    let old = parse(old);
+    let new = parse(new);
+
+    let names: BTreeSet<&String> = old.keys().chain(new.keys()).collect();
+    let empty = BTreeSet::new();
+    let mut changes = Vec::new();
+
+    for name in names {
+        let old_versions = old.get(name).unwrap_or(&empty);
+        let new_versions = new.get(name).unwrap_or(&empty);
+        if old_versions == new_versions {
+            continue;
+        }
+
+        // 同一依赖可能并存多个版本，优先取发生变化的版本
+        let from = old_versions.difference(new_versions).last().or(old_versions.iter().next_back()).cloned();
+        let to = new_versions.difference(old_versions).last().or(new_versions.iter().next_back()).cloned();
+        if from == to {
+            continue;
+        }
+
+        changes.push(DependencyChange { name: name.clone(), from, to });
+    }
+
+    changes
+}
+
+/// 合并多个锁文件的变化并去重（monorepo 中多个锁文件可能记录同一变化）
+pub(crate) fn merge(changes: impl Iterator<Item = DependencyChange>) -> Vec<DependencyChange> {
+    let mut merged: Vec<DependencyChange> = changes.collect();
+    merged.sort_by(|a, b| a.order().cmp(&b.order()).then(a.cmp(b)));
+    merged.dedup();
+    merged
+}
+
+/// 生成形如 "bump serde 1.0.190 → 1.0.195, add regex" 的摘要，调用方传入 `commit_lang()`
+pub(crate) fn summarize(changes: &[DependencyChange], lang: Lang) -> String {
+    let parts: Vec<String> = changes.iter().take(SUMMARY_LIMIT).map(|c| c.describe(lang)).collect();
+    let mut summary = parts.join(lookup(lang, "deps.separator"));
+    if changes.len() > SUMMARY_LIMIT {
+        summary.push_str(&fill(lookup(lang, "deps.more"), &[("count", (changes.len() - SUMMARY_LIMIT).to_string())]));
+    }
+    summary
+}
+
+pub(crate) fn details(changes: &[DependencyChange], lang: Lang) -> String {
+    changes.iter().map(|c| format!("- {}", c.describe(lang))).collect::<Vec<_>>().join("\n")
+}
+
+/// 依赖名 → 已解析的版本集合
+type Resolved = BTreeMap<String, BTreeSet<String>>;
+
+fn parse_lock_file(path: &str, content: &str) -> Resolved {
+    match file_name(path) {
+        "Cargo.lock" => parse_cargo_lock(content),
+        "package-lock.json" | "npm-shrinkwrap.json" => parse_npm_lock(content),
+        "pnpm-lock.yaml" => parse_pnpm_lock(content),
+        "yarn.lock" => parse_yarn_lock(content),
+        _ => Resolved::new(),
+    }
+}
+
+fn insert(resolved: &mut Resolved, name: &str, version: &str) {
+    if !name.is_empty() && !version.is_empty() {
+        resolved.entry(name.to_string()).or_default().insert(version.to_string());
+    }
+}
+
+/// `[[package]]` 中带 source 的条目；没有 source 的是本地 workspace 成员，不计入依赖
+fn parse_cargo_lock(content: &str) -> Resolved {
+    let mut resolved = Resolved::new();
+    let Ok(lock) = toml::from_str::<toml::Value>(content) else {
+        return resolved;
+    };
+
+    for package in lock.get("package").and_then(|p| p.as_array()).into_iter().flatten() {
+        if package.get("source").is_none() {
+            continue;
+        }
+        if let (Some(name), Some(version)) = (
+            package.get("name").and_then(|v| v.as_str()),
+            package.get("version").and_then(|v| v.as_str()),
+        ) {
+            insert(&mut resolved, name, version);
+        }
+    }
+
+    resolved
+}
+
+/// lockfileVersion 2/3 使用 `packages`（键为 node_modules 路径），1 使用嵌套的 `dependencies`
+fn parse_npm_lock(content: &str) -> Resolved {
+    let mut resolved = Resolved::new();
+    let Ok(lock) = serde_json::from_str::<serde_json::Value>(content) else {
+        return resolved;
+    };
+
+    if let Some(packages) = lock.get("packages").and_then(|p| p.as_object()) {
+        for (key, package) in packages {
+            let Some((_, name)) = key.rsplit_once("node_modules/") else {
+                continue;
+            };
+            if package.get("link").and_then(|l| l.as_bool()) == Some(true) {
+                continue;
+            }
+            if let Some(version) = package.get("version").and_then(|v| v.as_str()) {
+                insert(&mut resolved, name, version);
+            }
+        }
+        return resolved;
+    }
+
+    fn walk(deps: &serde_json::Value, resolved: &mut Resolved) {
+        for (name, dep) in deps.as_object().into_iter().flatten() {
+            if let Some(version) = dep.get("version").and_then(|v| v.as_str()) {
+                insert(resolved, name, version);
+            }
+            if let Some(nested) = dep.get("dependencies") {
+                walk(nested, resolved);
+            }
+        }
+    }
+    if let Some(deps) = lock.get("dependencies") {
+        walk(deps, &mut resolved);
+    }
+
+    resolved
+}
+
+/// `packages` 的键因版本而异：v5 "/foo/1.0.0_peer"，v6 "/foo@1.0.0(peer)"，v9 "foo@1.0.0"
+fn parse_pnpm_lock(content: &str) -> Resolved {
+    let mut resolved = Resolved::new();
+    let Ok(lock) = serde_yaml::from_str::<serde_yaml::Value>(content) else {
+        return resolved;
+    };
+
+    // v5 使用 "/" 分隔版本，"_" 之后为 peer 依赖
+    let v5 = match lock.get("lockfileVersion") {
+        Some(serde_yaml::Value::Number(n)) => n.as_f64().is_some_and(|v| v < 6.0),
+        Some(serde_yaml::Value::String(v)) => v.starts_with('5'),
+        _ => false,
+    };
+
+    for key in lock.get("packages").and_then(|p| p.as_mapping()).into_iter().flatten().map(|(k, _)| k) {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        let key = key.trim_start_matches('/');
+
+        let parsed = if v5 {
+            key.rsplit_once('/').map(|(name, version)| (name, version.split('_').next().unwrap_or(version)))
+        } else {
+            split_name_version(key.split('(').next().unwrap_or(key))
+        };
+        if let Some((name, version)) = parsed {
+            insert(&mut resolved, name, version);
+        }
+    }
+
+    resolved
+}
+
+/// 拆分 "name@version"，兼容 "@scope/name@version"
+fn split_name_version(spec: &str) -> Option<(&str, &str)> {
+    let at = spec.get(1..)?.rfind('@')? + 1;
+    Some((&spec[..at], &spec[at + 1..]))
+}
+
+/// 条目头为不缩进的 `"a@^1.0.0", a@^1.1.0:`（berry 为 `"a@npm:^1.0.0":`），其下的 `version` 为解析结果；
+/// berry 的 `__metadata` 不含 `@`，会被自然跳过
+fn parse_yarn_lock(content: &str) -> Resolved {
+    let mut resolved = Resolved::new();
+    let mut current: Option<String> = None;
+
+    for line in content.lines() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if !line.starts_with(' ') {
+            // berry 的 workspace 成员（"a@workspace:."）不计入依赖
+            if line.contains("@workspace:") {
+                current = None;
+                continue;
+            }
+            current = line
+                .trim_end_matches(':')
+                .split(',')
+                .next()
+                .map(|spec| spec.trim().trim_matches('"'))
+                .and_then(split_name_version)
+                .map(|(name, _)| name.to_string());
+            continue;
+        }
+
+        let trimmed = line.trim();
+        let version = trimmed
+            .strip_prefix("version ")
+            .or_else(|| trimmed.strip_prefix("version:"))
+            .map(|v| v.trim().trim_matches('"'));
+        if let (Some(name), Some(version)) = (&current, version) {
+            insert(&mut resolved, name, version);
+        }
+    }
+
+    resolved
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(name: &str, from: Option<&str>, to: Option<&str>) -> DependencyChange {
+        DependencyChange {
+            name: name.to_string(),
+            from: from.map(String::from),
+            to: to.map(String::from),
+        }
+    }
+
+    fn versions(resolved: &Resolved, name: &str) -> Vec<String> {
+        resolved.get(name).map(|v| v.iter().cloned().collect()).unwrap_or_default()
+    }
+
+    const CARGO_OLD: &str = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "serde"
+version = "1.0.190"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "log"
+version = "0.4.20"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    const CARGO_NEW: &str = r#"
+version = 3
+
+[[package]]
+name = "app"
+version = "0.2.0"
+
+[[package]]
+name = "serde"
+version = "1.0.195"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "regex"
+version = "1.10.2"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+
+    #[test]
+    fn cargo_lock_skips_workspace_members() {
+        let resolved = parse_cargo_lock(CARGO_OLD);
+        assert!(!resolved.contains_key("app"));
+        assert_eq!(versions(&resolved, "serde"), ["1.0.190"]);
+    }
+
+    #[test]
+    fn cargo_lock_diff_reports_added_removed_and_bumped() {
+        let changes = diff_lock_file("Cargo.lock", Some(CARGO_OLD), Some(CARGO_NEW));
+        assert_eq!(
+            changes,
+            [
+                change("log", Some("0.4.20"), None),
+                change("regex", None, Some("1.10.2")),
+                change("serde", Some("1.0.190"), Some("1.0.195")),
+            ]
+        );
+    }
+
+    #[test]
+    fn npm_lock_v3_handles_scoped_and_nested_packages() {
+        let old = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "app" },
+    "node_modules/@types/node": { "version": "20.8.0" },
+    "node_modules/lodash": { "version": "4.17.20" },
+    "node_modules/left-pad": { "version": "1.3.0" },
+    "packages/ui": { "version": "0.1.0" },
+    "node_modules/ui": { "resolved": "packages/ui", "link": true }
+  }
+}"#;
+        let new = r#"{
+  "lockfileVersion": 3,
+  "packages": {
+    "": { "name": "app" },
+    "node_modules/@types/node": { "version": "20.10.4" },
+    "node_modules/lodash": { "version": "4.17.21" },
+    "node_modules/a/node_modules/lodash": { "version": "3.10.1" },
+    "node_modules/@scope/kit": { "version": "2.0.0" },
+    "node_modules/ui": { "resolved": "packages/ui", "link": true }
+  }
+}"#;
+
+        let resolved = parse_npm_lock(new);
+        assert_eq!(versions(&resolved, "lodash"), ["3.10.1", "4.17.21"]);
+        assert!(!resolved.contains_key("ui"));
+
+        let changes = diff_lock_file("package-lock.json", Some(old), Some(new));
+        assert_eq!(
+            changes,
+            [
+                change("@scope/kit", None, Some("2.0.0")),
+                change("@types/node", Some("20.8.0"), Some("20.10.4")),
+                change("left-pad", Some("1.3.0"), None),
+                change("lodash", Some("4.17.20"), Some("4.17.21")),
+            ]
+        );
+    }
+
+    #[test]
+    fn npm_lock_v1_walks_nested_dependencies() {
+        let lock = r#"{
+  "lockfileVersion": 1,
+  "dependencies": {
+    "@babel/core": {
+      "version": "7.23.0",
+      "dependencies": { "semver": { "version": "6.3.1" } }
+    },
+    "semver": { "version": "7.5.4" }
+  }
+}"#;
+        let resolved = parse_npm_lock(lock);
+        assert_eq!(versions(&resolved, "@babel/core"), ["7.23.0"]);
+        assert_eq!(versions(&resolved, "semver"), ["6.3.1", "7.5.4"]);
+    }
+
+    #[test]
+    fn pnpm_lock_parses_v5_v6_and_v9_keys() {
+        let v5 = "lockfileVersion: 5.4\npackages:\n  /@types/node/20.8.0:\n    dev: true\n  /react-dom/18.2.0_react@18.2.0:\n    dev: false\n";
+        let resolved = parse_pnpm_lock(v5);
+        assert_eq!(versions(&resolved, "@types/node"), ["20.8.0"]);
+        assert_eq!(versions(&resolved, "react-dom"), ["18.2.0"]);
+
+        let v6 = "lockfileVersion: '6.0'\npackages:\n  /@types/node@20.10.4:\n    dev: true\n  /react-dom@18.2.0(react@18.2.0):\n    dev: false\n";
+        let resolved = parse_pnpm_lock(v6);
+        assert_eq!(versions(&resolved, "@types/node"), ["20.10.4"]);
+        assert_eq!(versions(&resolved, "react-dom"), ["18.2.0"]);
+
+        let v9 = "lockfileVersion: '9.0'\npackages:\n  '@types/node@20.10.4':\n    resolution: {}\n  lodash@4.17.21:\n    resolution: {}\n";
+        let changes = diff_lock_file("pnpm-lock.yaml", Some(v5), Some(v9));
+        assert_eq!(
+            changes,
+            [
+                change("@types/node", Some("20.8.0"), Some("20.10.4")),
+                change("lodash", None, Some("4.17.21")),
+                change("react-dom", Some("18.2.0"), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn yarn_lock_classic_handles_multiple_specifiers() {
+        let old = r#"# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.22.13":
+  version "7.22.13"
+  resolved "https://registry.yarnpkg.com/@babel/code-frame/-/code-frame-7.22.13.tgz"
+
+lodash@^4.17.20, lodash@^4.17.21:
+  version "4.17.20"
+
+left-pad@^1.3.0:
+  version "1.3.0"
+"#;
+        let new = r#"# yarn lockfile v1
+
+
+"@babel/code-frame@^7.0.0", "@babel/code-frame@^7.22.13":
+  version "7.23.5"
+
+lodash@^4.17.20, lodash@^4.17.21:
+  version "4.17.21"
+
+"@scope/kit@^2.0.0":
+  version "2.0.0"
+"#;
+
+        let resolved = parse_yarn_lock(old);
+        assert_eq!(versions(&resolved, "@babel/code-frame"), ["7.22.13"]);
+        assert_eq!(versions(&resolved, "lodash"), ["4.17.20"]);
+
+        let changes = diff_lock_file("yarn.lock", Some(old), Some(new));
+        assert_eq!(
+            changes,
+            [
+                change("@babel/code-frame", Some("7.22.13"), Some("7.23.5")),
+                change("@scope/kit", None, Some("2.0.0")),
+                change("left-pad", Some("1.3.0"), None),
+                change("lodash", Some("4.17.20"), Some("4.17.21")),
+            ]
+        );
+    }
+
+    #[test]
+    fn yarn_lock_berry_skips_metadata_and_workspaces() {
+        let lock = r#"__metadata:
+  version: 6
+  cacheKey: 8
+
+"@types/node@npm:^20.8.0, @types/node@npm:^20.10.0":
+  version: 20.10.4
+  resolution: "@types/node@npm:20.10.4"
+
+"app@workspace:.":
+  version: 0.0.0-use.local
+"#;
+        let resolved = parse_yarn_lock(lock);
+        assert_eq!(versions(&resolved, "@types/node"), ["20.10.4"]);
+        assert!(!resolved.contains_key("app"));
+        assert_eq!(resolved.len(), 1);
+    }
+
+    #[test]
+    fn summary_lists_bumps_first_and_counts_the_rest() {
+        let changes = merge(
+            [
+                change("regex", None, Some("1.10.2")),
+                change("serde", Some("1.0.190"), Some("1.0.195")),
+                change("log", Some("0.4.20"), None),
+                change("anyhow", None, Some("1.0.75")),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(summarize(&changes, Lang::En), "bump serde 1.0.190 → 1.0.195, add anyhow, add regex, and 1 more");
+    }
+
+    #[test]
+    fn summary_follows_commit_language() {
+        let changes = merge(
+            [
+                change("regex", None, Some("1.10.2")),
+                change("serde", Some("1.0.190"), Some("1.0.195")),
+                change("log", Some("0.4.20"), None),
+                change("anyhow", None, Some("1.0.75")),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(summarize(&changes, Lang::ZhCn), "升级 serde 1.0.190 → 1.0.195、新增 anyhow、新增 regex 等 1 项");
+        assert_eq!(details(&changes[3..], Lang::ZhCn), "- 移除 log");
+    }
+}
@@ -0,0 +1,110 @@
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use crate::config::load_settings;
+use crate::git::{git_output, git_output_with_input};
+use std::path::Path;
+
+/// 仓库根目录下的忽略文件，语法与 .gitignore 相同
+pub(crate) const IGNORE_FILE: &str = ".cyber-zenignore";
+
+/// gcm 不自动暂存、也不参与提交信息生成的文件
+pub(crate) struct IgnoreRules {
+    matcher: Gitignore,
+}
+
+impl IgnoreRules {
+    /// 合并 settings.toml 中的 `gcm.ignore` 和仓库的 `.cyber-zenignore`
+    pub fn load(repo: &Path) -> Result<Self, Box<dyn std::error::Error>> {
+        let settings = load_settings(repo)?;
+        let mut builder = GitignoreBuilder::new(repo);
+
+        for pattern in &settings.gcm.ignore {
+            builder
+                .add_line(None, pattern)
+                .map_err(|e| t!("ignores.invalid_pattern", pattern = pattern, error = e))?;
+        }
+
+        let ignore_file = repo.join(IGNORE_FILE);
+        if ignore_file.is_file() {
+            if let Some(e) = builder.add(&ignore_file) {
+                return Err(t!("common.parse_failed", file = IGNORE_FILE, error = e).into());
+            }
+        }
+
+        Ok(IgnoreRules { matcher: builder.build()? })
+    }
+
+    /// 路径相对仓库根目录；目录规则（如 "dist/"）对其下所有文件生效
+    pub fn is_ignored(&self, path: &str) -> bool {
+        self.matcher
+            .matched_path_or_any_parents(path, false)
+            .is_ignore()
+    }
+}
+
+/// `git status --porcelain` 中的一项
+pub(crate) struct StatusEntry {
+    /// 两列状态码，如 " M"、"??"、"R "
+    pub xy: String,
+    pub path: String,
+    /// 重命名前的路径
+    pub orig_path: Option<String>,
+}
+
+/// 列出工作区变更，未跟踪目录展开为单个文件，以便逐个文件应用忽略规则
+pub(crate) fn status_entries(repo: &Path) -> Result<Vec<StatusEntry>, Box<dyn std::error::Error>> {
+    let output = git_output(repo, &["status", "--porcelain", "-z", "--untracked-files=all"])?;
+    let mut fields = output.split('\0');
+    let mut entries = Vec::new();
+
+    while let Some(field) = fields.next() {
+        if field.len() < 4 {
+            continue;
+        }
+
+        let xy = field[..2].to_string();
+        let path = field[3..].to_string();
+        // -z 格式下重命名/复制的原路径紧跟在下一个字段
+        let orig_path = if xy.starts_with('R') || xy.starts_with('C') {
+            fields.next().map(String::from)
+        } else {
+            None
+        };
+
+        entries.push(StatusEntry { xy, path, orig_path });
+    }
+
+    Ok(entries)
+}
+
+/// 只暂存未被忽略的变更，返回被跳过的文件
+pub(crate) fn stage_changes(repo: &Path, rules: &IgnoreRules) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut paths = Vec::new();
+    let mut skipped = Vec::new();
+
+    for entry in status_entries(repo)? {
+        if rules.is_ignored(&entry.path) {
+            skipped.push(entry.path);
+            continue;
+        }
+        // 重命名只出现在暂存区中，原路径的删除已经暂存，只需添加新路径
+        paths.push(entry.path);
+    }
+
+    if paths.is_empty() {
+        return Err(t!("ignores.nothing_to_commit").into());
+    }
+
+    // 路径按字面匹配（不解析 `:` 前缀和通配符），经标准输入传递以免超出命令行长度限制
+    let mut input = Vec::new();
+    for path in &paths {
+        input.extend_from_slice(path.as_bytes());
+        input.push(0);
+    }
+    git_output_with_input(
+        repo,
+        &["--literal-pathspecs", "add", "-A", "--pathspec-from-file=-", "--pathspec-file-nul"],
+        &input,
+    )?;
+
+    Ok(skipped)
+}
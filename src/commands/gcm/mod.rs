@@ -0,0 +1,642 @@
+mod analyze;
+mod deps;
+mod ignores;
+mod packages;
+mod trailers;
+mod workspace;
+
+use colored::*;
+use crate::config::{default_style_name, load_settings, CommitStyle, FileTypeManager};
+use crate::git::{check_git_repo, exec_git_command, git_output};
+use crate::i18n::commit_lang;
+use deps::DependencyChange;
+use ignores::{stage_changes, status_entries, IgnoreRules};
+use packages::{package_label, PackageMap};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// 空树对象，用于尚无提交的仓库计算行数统计
+const EMPTY_TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+
+#[derive(Serialize)]
+pub struct ChangeInfo {
+    /// 显示用的路径，重命名时为 "old -> new"
+    file: String,
+    /// 当前路径
+    path: String,
+    /// 重命名前的路径
+    old_path: Option<String>,
+    status: String,
+    category: String,
+    /// 分类规则名，供英文风格的摘要使用
+    #[serde(skip)]
+    category_name: Option<String>,
+    file_type: String,
+    /// 新增行数，二进制文件为空
+    additions: Option<u32>,
+    /// 删除行数，二进制文件为空
+    deletions: Option<u32>,
+    /// monorepo 中所属的包，非 monorepo 或不属于任何包时为空
+    package: Option<String>,
+    /// 锁文件中解析出的依赖版本变化
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    dependencies: Vec<DependencyChange>,
+}
+
+/// 生成的提交信息各组成部分
+#[derive(Serialize)]
+pub struct CommitProposal {
+    #[serde(rename = "type")]
+    commit_type: String,
+    scope: Option<String>,
+    summary: String,
+    /// 按提交风格渲染后的标题
+    header: String,
+    body: String,
+}
+
+impl CommitProposal {
+    fn new(style: &CommitStyle, commit_type: &str, scope: Option<String>, summary: String, body: String) -> Self {
+        CommitProposal {
+            header: style.render_header(commit_type, scope.as_deref(), &summary),
+            commit_type: style.commit_type(commit_type),
+            scope,
+            summary,
+            body,
+        }
+    }
+
+    pub fn render(&self) -> String {
+        if self.body.is_empty() {
+            self.header.clone()
+        } else {
+            format!("{}\n\n{}", self.header, self.body)
+        }
+    }
+}
+
+pub use analyze::OutputFormat;
+pub use trailers::CommitOptions;
+
+/// gcm 命令行参数
+pub struct GcmOptions {
+    pub message: Option<String>,
+    pub workspace: Option<String>,
+    pub sign: bool,
+    pub signoff: bool,
+    pub co_authors: Vec<String>,
+    pub pair: bool,
+    pub analyze: bool,
+    pub format: OutputFormat,
+    pub allow_cross_package: bool,
+    pub style: Option<String>,
+}
+
+pub fn run_gcm(options: GcmOptions) -> Result<(), Box<dyn std::error::Error>> {
+    if options.analyze {
+        return analyze::run_analyze(options.format, options.style.as_deref());
+    }
+
+    let settings = load_settings(Path::new(options.workspace.as_deref().unwrap_or(".")))?;
+
+    if let Some(dir) = options.workspace {
+        // 工作区模式不做交互式结对选择，只使用命令行和配置中的合作者
+        let commit_options = CommitOptions::resolve(
+            Path::new(&dir),
+            &settings.gcm,
+            options.sign,
+            options.signoff,
+            &options.co_authors,
+            false,
+        )?;
+        return workspace::run_workspace(&dir, &commit_options, options.style.as_deref());
+    }
+
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let commit_options = CommitOptions::resolve(
+        repo,
+        &settings.gcm,
+        options.sign,
+        options.signoff,
+        &options.co_authors,
+        options.pair,
+    )?;
+
+    if settings.gcm.strict_packages && !options.allow_cross_package {
+        check_single_package(repo)?;
+    }
+
+    let msg = if let Some(m) = options.message {
+        println!("{} {}", t!("gcm.using_message").cyan(), m);
+        m
+    } else {
+        println!("{}", t!("gcm.analyzing").yellow());
+        match generate_commit_message(options.style.as_deref()) {
+            Ok(m) => {
+                println!("{}", t!("gcm.generated").green());
+                m
+            }
+            Err(e) => {
+                println!("{} {}", t!("gcm.generate_failed").red(), e);
+                println!("{}", t!("gcm.default_message").yellow());
+                "update".to_string()
+            }
+        }
+    };
+
+    let msg = commit_options.apply_trailers(repo, &msg)?;
+
+    println!("{}", t!("gcm.start").green());
+    println!("{} {}", t!("gcm.message").cyan(), msg);
+
+    println!("{}", t!("gcm.run_add").yellow());
+    let skipped = stage_changes(repo, &IgnoreRules::load(repo)?)?;
+    for path in &skipped {
+        println!("  {} {}", t!("gcm.skip_ignored").yellow(), path);
+    }
+    println!("{}", t!("gcm.add_done").green());
+
+    let extra_args = commit_options.commit_args();
+    let mut commit_args = vec!["commit", "-m", &msg, "--no-verify"];
+    commit_args.extend(extra_args.iter().map(|s| s.as_str()));
+
+    println!(
+        "{}",
+        t!("gcm.run_commit", subject = msg.lines().next().unwrap_or(""), args = extra_args.join(" ")).yellow()
+    );
+    exec_git_command(repo, &commit_args)?;
+    println!("{}", t!("gcm.commit_done").green());
+
+    println!("{}", t!("gcm.run_push").yellow());
+    exec_git_command(repo, &["push"])?;
+    println!("{}", t!("gcm.push_done").green());
+
+    println!("{}", t!("gcm.done").green());
+    Ok(())
+}
+
+/// 严格模式下拒绝跨多个包的提交
+fn check_single_package(repo: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let file_type_manager = FileTypeManager::new()?;
+    let changes = analyze_git_changes(repo, &file_type_manager)?;
+
+    let packages = touched_packages(&changes);
+    if packages.len() > 1 {
+        let packages = packages.into_iter().map(package_label).collect::<Vec<_>>().join(", ");
+        return Err(t!("gcm.cross_package", packages = packages).into());
+    }
+
+    Ok(())
+}
+
+/// 变更涉及的包，不属于任何包的文件记为 `None`（根目录）；非 monorepo 时为空
+fn touched_packages(changes: &[ChangeInfo]) -> BTreeSet<Option<&str>> {
+    if changes.iter().all(|c| c.package.is_none()) {
+        return BTreeSet::new();
+    }
+
+    changes.iter().map(|c| c.package.as_deref()).collect()
+}
+
+/// 命令行 `--style` 优先，其次为仓库 `.cyber-zen.toml` 和全局 settings.toml 中的 `gcm.style`，
+/// 都未配置时按 `i18n.commit_lang` 选择默认风格
+pub(crate) fn resolve_style<'a>(
+    repo: &Path,
+    file_type_manager: &'a FileTypeManager,
+    style: Option<&str>,
+) -> Result<&'a CommitStyle, Box<dyn std::error::Error>> {
+    let name = match style {
+        Some(name) => name.to_string(),
+        None => load_settings(repo)?.gcm.style.unwrap_or_else(|| default_style_name().to_string()),
+    };
+    file_type_manager.commit_style(&name)
+}
+
+fn generate_commit_message(style: Option<&str>) -> Result<String, Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let file_type_manager = FileTypeManager::new()?;
+    let style = resolve_style(repo, &file_type_manager, style)?;
+    let changes = analyze_git_changes(repo, &file_type_manager)?;
+
+    display_changes(&changes);
+    let message = generate_message_from_changes(&changes, &file_type_manager, style);
+
+    println!("\n{}", t!("gcm.generated_message").cyan());
+    println!("{}", message);
+
+    if !confirm_with_user(&t!("gcm.confirm")) {
+        return Err(t!("common.cancelled").into());
+    }
+
+    Ok(message)
+}
+
+pub(crate) fn analyze_git_changes(repo: &Path, file_type_manager: &FileTypeManager) -> Result<Vec<ChangeInfo>, Box<dyn std::error::Error>> {
+    let entries = status_entries(repo)?;
+    let rules = IgnoreRules::load(repo)?;
+
+    let base = if git_output(repo, &["rev-parse", "--verify", "-q", "HEAD"]).is_ok() {
+        "HEAD"
+    } else {
+        EMPTY_TREE
+    };
+    let line_stats = collect_line_stats(repo, &["diff", base, "--numstat", "--no-renames", "-z"]);
+    let packages = PackageMap::detect(repo);
+
+    let mut changes = Vec::new();
+
+    for entry in entries {
+        if rules.is_ignored(&entry.path) {
+            continue;
+        }
+
+        let status = parse_status(&entry.xy);
+        let current_path = entry.path.as_str();
+        let file = match &entry.orig_path {
+            Some(orig) => format!("{} -> {}", orig, current_path),
+            None => current_path.to_string(),
+        };
+
+        let (additions, deletions) = match line_stats.get(current_path) {
+            Some(&stats) => stats,
+            None if entry.xy == "??" => count_untracked_lines(&repo.join(current_path)),
+            None => (None, None),
+        };
+
+        let dependencies = if deps::is_lock_file(current_path) {
+            let old = entry.orig_path.as_deref().unwrap_or(current_path);
+            deps::diff_lock_file(
+                current_path,
+                deps::read_revision(repo, "HEAD", old).as_deref(),
+                deps::read_worktree(repo, current_path).as_deref(),
+            )
+        } else {
+            Vec::new()
+        };
+
+        let change = ChangeInfo {
+            file,
+            path: current_path.to_string(),
+            old_path: entry.orig_path.clone(),
+            status,
+            category: file_type_manager.get_file_category(repo, current_path),
+            category_name: file_type_manager.get_file_category_name(repo, current_path),
+            file_type: file_type_manager.get_file_type(repo, current_path),
+            additions,
+            deletions,
+            package: packages.owner(current_path).map(|p| p.name.clone()),
+            dependencies,
+        };
+
+        changes.push(change);
+    }
+
+    Ok(changes)
+}
+
+/// 只分析暂存区的变更，供 Git hooks 在 `git commit` 时使用
+pub(crate) fn analyze_staged_changes(repo: &Path, file_type_manager: &FileTypeManager) -> Result<Vec<ChangeInfo>, Box<dyn std::error::Error>> {
+    // -z 避免 git 给非 ASCII 路径加引号和转义
+    let output_str = git_output(repo, &["diff", "--cached", "--name-status", "-z"])?;
+    let line_stats = collect_line_stats(repo, &["diff", "--cached", "--numstat", "--no-renames", "-z"]);
+    let packages = PackageMap::detect(repo);
+    let rules = IgnoreRules::load(repo)?;
+
+    let mut changes = Vec::new();
+    let mut fields = output_str.split('\0');
+
+    while let Some(code) = fields.next() {
+        if code.is_empty() {
+            continue;
+        }
+        let Some(first) = fields.next() else {
+            break;
+        };
+
+        // 重命名/复制形如 "R100\0old\0new"，取新路径
+        let status = code.chars().next().unwrap_or('M').to_string();
+        let (old, file) = if code.starts_with('R') || code.starts_with('C') {
+            match fields.next() {
+                Some(new) => (first, new.to_string()),
+                None => break,
+            }
+        } else {
+            (first, first.to_string())
+        };
+        let old_path = (old != file).then(|| old.to_string());
+        if rules.is_ignored(&file) {
+            continue;
+        }
+        let (additions, deletions) = line_stats.get(&file).copied().unwrap_or((None, None));
+
+        let dependencies = if deps::is_lock_file(&file) {
+            deps::diff_lock_file(
+                &file,
+                deps::read_revision(repo, "HEAD", old).as_deref(),
+                deps::read_revision(repo, "", &file).as_deref(),
+            )
+        } else {
+            Vec::new()
+        };
+
+        changes.push(ChangeInfo {
+            dependencies,
+            package: packages.owner(&file).map(|p| p.name.clone()),
+            category: file_type_manager.get_file_category(repo, &file),
+            category_name: file_type_manager.get_file_category_name(repo, &file),
+            file_type: file_type_manager.get_file_type(repo, &file),
+            path: file.clone(),
+            old_path,
+            file,
+            status,
+            additions,
+            deletions,
+        });
+    }
+
+    Ok(changes)
+}
+
+/// 解析 `git diff --numstat -z` 输出，按新路径索引；二进制文件的行数为 "-"
+fn collect_line_stats(repo: &Path, args: &[&str]) -> HashMap<String, (Option<u32>, Option<u32>)> {
+    let mut stats = HashMap::new();
+
+    if let Ok(output) = git_output(repo, args) {
+        let mut fields = output.split('\0');
+        while let Some(field) = fields.next() {
+            let parts: Vec<&str> = field.splitn(3, '\t').collect();
+            if parts.len() != 3 {
+                continue;
+            }
+            // 重命名形如 "add\tdel\t\0old\0new"，路径字段为空
+            let path = if parts[2].is_empty() {
+                fields.next();
+                match fields.next() {
+                    Some(new) => new,
+                    None => break,
+                }
+            } else {
+                parts[2]
+            };
+            stats.insert(path.to_string(), (parts[0].parse().ok(), parts[1].parse().ok()));
+        }
+    }
+
+    stats
+}
+
+/// 未跟踪文件不在 diff 中，按文本行数计为新增
+fn count_untracked_lines(path: &Path) -> (Option<u32>, Option<u32>) {
+    match fs::read(path) {
+        Ok(data) if !data.contains(&0) => {
+            let text = String::from_utf8_lossy(&data);
+            (Some(text.lines().count() as u32), Some(0))
+        }
+        _ => (None, None),
+    }
+}
+
+/// 将 porcelain 的两列状态合并为单个状态：优先暂存区，未跟踪文件视为新增
+fn parse_status(xy: &str) -> String {
+    let mut chars = xy.chars();
+    let index = chars.next().unwrap_or(' ');
+    let worktree = chars.next().unwrap_or(' ');
+
+    match (index, worktree) {
+        ('?', _) => "A".to_string(),
+        (' ', w) => w.to_string(),
+        (i, _) => i.to_string(),
+    }
+}
+
+fn display_changes(changes: &[ChangeInfo]) {
+    println!("{}\n", t!("gcm.detected").cyan());
+    println!("{}", t!("gcm.file_status").yellow());
+
+    for change in changes {
+        match change.status.as_str() {
+            "A" => println!("  {} {}", t!("gcm.status_added").green(), change.file),
+            "M" => println!("  {} {}", t!("gcm.status_modified").blue(), change.file),
+            "D" => println!("  {} {}", t!("gcm.status_deleted").red(), change.file),
+            "R" => println!("  {} {}", t!("gcm.status_renamed").yellow(), change.file),
+            _ => println!("  {} {}: {}", "❓".cyan(), change.status, change.file),
+        }
+    }
+
+    display_package_stats(changes);
+    display_change_stats(changes);
+}
+
+/// monorepo 中按包汇总文件数和行数
+fn display_package_stats(changes: &[ChangeInfo]) {
+    if changes.iter().all(|c| c.package.is_none()) {
+        return;
+    }
+
+    let mut packages: BTreeMap<Option<&str>, (usize, u32, u32)> = BTreeMap::new();
+    for change in changes {
+        let entry = packages.entry(change.package.as_deref()).or_default();
+        entry.0 += 1;
+        entry.1 += change.additions.unwrap_or(0);
+        entry.2 += change.deletions.unwrap_or(0);
+    }
+
+    println!();
+    println!("{}", t!("gcm.package_stats").yellow());
+    for (name, (files, additions, deletions)) in packages {
+        println!(
+            "  {}: {} {} {}",
+            package_label(name).cyan(),
+            t!("gcm.file_count", count = files),
+            format!("+{}", additions).green(),
+            format!("-{}", deletions).red()
+        );
+    }
+}
+
+fn display_change_stats(changes: &[ChangeInfo]) {
+    let mut added = 0;
+    let mut modified = 0;
+    let mut deleted = 0;
+
+    for change in changes {
+        match change.status.as_str() {
+            "A" => added += 1,
+            "M" => modified += 1,
+            "D" => deleted += 1,
+            _ => {}
+        }
+    }
+
+    println!();
+    println!("{}", t!("gcm.stats").cyan());
+    println!("  {}", t!("gcm.stats_added", count = added));
+    println!("  {}", t!("gcm.stats_modified", count = modified));
+    println!("  {}", t!("gcm.stats_deleted", count = deleted));
+    println!("  {}", t!("gcm.stats_total", count = changes.len()));
+}
+
+pub(crate) fn generate_message_from_changes(changes: &[ChangeInfo], file_type_manager: &FileTypeManager, style: &CommitStyle) -> String {
+    if changes.is_empty() {
+        return "update".to_string();
+    }
+
+    propose_commit(changes, file_type_manager, style).render()
+}
+
+fn propose_commit(changes: &[ChangeInfo], file_type_manager: &FileTypeManager, style: &CommitStyle) -> CommitProposal {
+    if let Some(proposal) = propose_dependency_commit(changes, style) {
+        return proposal;
+    }
+
+    let mut added = 0;
+    let mut modified = 0;
+    let mut deleted = 0;
+    let mut categories: HashMap<String, i32> = HashMap::new();
+
+    for change in changes {
+        match change.status.as_str() {
+            "A" => added += 1,
+            "M" => modified += 1,
+            "D" => deleted += 1,
+            _ => {}
+        }
+        *categories.entry(category_label(change, style)).or_insert(0) += 1;
+    }
+
+    // 所有变更都在同一个包内时，以包名作为 scope
+    let packages = touched_packages(changes);
+    let scope = match packages.iter().next() {
+        Some(&Some(name)) if packages.len() == 1 => Some(name.to_string()),
+        _ => None,
+    };
+
+    CommitProposal::new(
+        style,
+        &file_type_manager.get_commit_type(added, modified, deleted),
+        scope,
+        generate_summary(changes, &categories, file_type_manager, style),
+        generate_details(changes, file_type_manager, style),
+    )
+}
+
+/// 只涉及依赖清单和锁文件时，根据锁文件中解析出的版本变化生成 `chore(deps)`
+fn propose_dependency_commit(changes: &[ChangeInfo], style: &CommitStyle) -> Option<CommitProposal> {
+    if !changes.iter().all(|c| deps::is_dependency_file(&c.path)) {
+        return None;
+    }
+
+    let dependencies = deps::merge(changes.iter().flat_map(|c| c.dependencies.iter().cloned()));
+    if dependencies.is_empty() {
+        return None;
+    }
+
+    Some(CommitProposal::new(
+        style,
+        "chore",
+        Some("deps".to_string()),
+        deps::summarize(&dependencies, commit_lang()),
+        deps::details(&dependencies, commit_lang()),
+    ))
+}
+
+/// 风格要求分类规则名时使用规则名，否则使用分类描述
+fn category_label(change: &ChangeInfo, style: &CommitStyle) -> String {
+    if style.category_names {
+        change.category_name.clone().unwrap_or_else(|| "files".to_string())
+    } else {
+        change.category.clone()
+    }
+}
+
+/// 动作词优先取风格预设，其次为 commit_templates.actions
+fn action_word(action: &str, file_type_manager: &FileTypeManager, style: &CommitStyle) -> String {
+    style
+        .actions
+        .get(action)
+        .cloned()
+        .unwrap_or_else(|| file_type_manager.get_action_description(action))
+}
+
+fn status_action(status: &str) -> Option<&'static str> {
+    match status {
+        "A" => Some("added"),
+        "M" => Some("modified"),
+        "D" => Some("deleted"),
+        "R" => Some("renamed"),
+        _ => None,
+    }
+}
+
+fn generate_summary(
+    changes: &[ChangeInfo],
+    categories: &HashMap<String, i32>,
+    file_type_manager: &FileTypeManager,
+    style: &CommitStyle,
+) -> String {
+    let updated = action_word("updated", file_type_manager, style);
+    let multiple = |categories: &str| style.multiple.replace("{action}", &updated).replace("{categories}", categories);
+
+    if changes.len() == 1 {
+        let change = &changes[0];
+        return match status_action(&change.status) {
+            Some(action) => style
+                .single
+                .replace("{action}", &action_word(action, file_type_manager, style))
+                .replace("{category}", &category_label(change, style)),
+            None => style.fallback.clone(),
+        };
+    }
+
+    if categories.len() == 1 {
+        if let Some(category) = categories.keys().next() {
+            return multiple(category);
+        }
+    }
+
+    let mut main_categories: Vec<(&String, &i32)> = categories
+        .iter()
+        .filter(|(_, &count)| count > 1)
+        .collect();
+    main_categories.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let main_categories: Vec<String> = main_categories.into_iter().map(|(c, _)| c.clone()).collect();
+
+    if !main_categories.is_empty() {
+        return multiple(&main_categories.join(&style.separator));
+    }
+
+    style.fallback.clone()
+}
+
+fn generate_details(changes: &[ChangeInfo], file_type_manager: &FileTypeManager, style: &CommitStyle) -> String {
+    let mut details = Vec::new();
+
+    for change in changes {
+        let action = match status_action(&change.status) {
+            Some(action) => action_word(action, file_type_manager, style),
+            None => change.status.clone(),
+        };
+
+        details.push(style.detail.replace("{action}", &action).replace("{file}", &change.file));
+    }
+
+    details.join("\n")
+}
+
+fn confirm_with_user(prompt: &str) -> bool {
+    print!("{}", prompt);
+    io::stdout().flush().unwrap();
+
+    let mut response = String::new();
+    io::stdin().read_line(&mut response).unwrap();
+
+    let response = response.trim().to_lowercase();
+    response.is_empty() || response == "y" || response == "yes"
+}
@@ -0,0 +1,256 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::fs;
+use std::path::Path;
+
+use super::workspace::SKIP_DIRS;
+
+/// 分组时的显示名；不属于任何包（`None`）的文件，如仓库根目录的配置，显示为根目录
+pub(crate) fn package_label(package: Option<&str>) -> String {
+    package.map(String::from).unwrap_or_else(|| t!("packages.root"))
+}
+
+/// monorepo 中的一个包
+pub(crate) struct Package {
+    pub name: String,
+    /// 相对仓库根目录的路径，根目录本身为空字符串
+    pub path: String,
+}
+
+/// 仓库中检测到的所有包，按路径从深到浅排列，便于按最长前缀查找所属包
+pub(crate) struct PackageMap {
+    packages: Vec<Package>,
+}
+
+impl PackageMap {
+    /// 依次检测 Cargo workspace、npm/yarn/pnpm workspaces 和 Go 模块；
+    /// 只有一个包的普通仓库视为非 monorepo，返回空表
+    pub fn detect(repo: &Path) -> Self {
+        let mut manifests = Vec::new();
+        collect_manifest_dirs(repo, "", &mut manifests);
+
+        let mut packages = Vec::new();
+        packages.extend(detect_cargo(repo, &manifests));
+        packages.extend(detect_node(repo, &manifests));
+        packages.extend(detect_go(repo, &manifests));
+
+        if packages.len() < 2 {
+            packages.clear();
+        }
+
+        // 同一目录可能同时是多种包，保留先检测到的
+        let mut seen = std::collections::HashSet::new();
+        packages.retain(|p| seen.insert(p.path.clone()));
+        packages.sort_by(|a, b| b.path.len().cmp(&a.path.len()).then(a.path.cmp(&b.path)));
+
+        PackageMap { packages }
+    }
+
+    /// 文件所属的包（路径最长的祖先目录）
+    pub fn owner(&self, file: &str) -> Option<&Package> {
+        self.packages.iter().find(|p| {
+            p.path.is_empty() || file == p.path || file.starts_with(&format!("{}/", p.path))
+        })
+    }
+}
+
+/// 清单文件及其所在目录
+struct ManifestDir {
+    dir: String,
+    file: &'static str,
+}
+
+const MANIFEST_FILES: &[&str] = &["Cargo.toml", "package.json", "go.mod"];
+
+fn collect_manifest_dirs(repo: &Path, rel: &str, manifests: &mut Vec<ManifestDir>) {
+    let dir = if rel.is_empty() { repo.to_path_buf() } else { repo.join(rel) };
+
+    for file in MANIFEST_FILES {
+        if dir.join(file).is_file() {
+            manifests.push(ManifestDir { dir: rel.to_string(), file });
+        }
+    }
+
+    let Ok(entries) = fs::read_dir(&dir) else {
+        return;
+    };
+    let mut entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().to_string();
+        if SKIP_DIRS.contains(&name.as_str()) || name.starts_with('.') {
+            continue;
+        }
+
+        let child = if rel.is_empty() { name } else { format!("{}/{}", rel, name) };
+        collect_manifest_dirs(repo, &child, manifests);
+    }
+}
+
+/// 由 workspace 成员模式构建匹配器，`!` 开头的模式表示排除
+fn build_member_globs(patterns: &[String]) -> (GlobSet, GlobSet) {
+    let mut include = GlobSetBuilder::new();
+    let mut exclude = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        let (negated, pattern) = match pattern.strip_prefix('!') {
+            Some(rest) => (true, rest),
+            None => (false, pattern.as_str()),
+        };
+        let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+
+        let Ok(glob) = GlobBuilder::new(pattern).literal_separator(true).build() else {
+            continue;
+        };
+        if negated {
+            exclude.add(glob);
+        } else {
+            include.add(glob);
+        }
+    }
+
+    (
+        include.build().unwrap_or_else(|_| GlobSet::empty()),
+        exclude.build().unwrap_or_else(|_| GlobSet::empty()),
+    )
+}
+
+fn workspace_members<'a>(
+    manifests: &'a [ManifestDir],
+    file: &str,
+    patterns: &[String],
+    excludes: &[String],
+) -> Vec<&'a str> {
+    let mut all: Vec<String> = patterns.to_vec();
+    all.extend(excludes.iter().map(|e| format!("!{}", e)));
+    let (include, exclude) = build_member_globs(&all);
+
+    manifests
+        .iter()
+        .filter(|m| m.file == file && !m.dir.is_empty())
+        .filter(|m| include.is_match(&m.dir) && !exclude.is_match(&m.dir))
+        .map(|m| m.dir.as_str())
+        .collect()
+}
+
+fn string_list(value: Option<&toml::Value>) -> Vec<String> {
+    value
+        .and_then(|v| v.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn detect_cargo(repo: &Path, manifests: &[ManifestDir]) -> Vec<Package> {
+    let Some(root) = read_toml(&repo.join("Cargo.toml")) else {
+        return Vec::new();
+    };
+    let Some(workspace) = root.get("workspace") else {
+        return Vec::new();
+    };
+
+    let members = string_list(workspace.get("members"));
+    let excludes = string_list(workspace.get("exclude"));
+
+    let mut packages = Vec::new();
+    for dir in workspace_members(manifests, "Cargo.toml", &members, &excludes) {
+        let name = read_toml(&repo.join(dir).join("Cargo.toml"))
+            .and_then(|t| t.get("package")?.get("name")?.as_str().map(String::from));
+        if let Some(name) = name {
+            packages.push(Package { name, path: dir.to_string() });
+        }
+    }
+
+    // 根目录同时是包时，未归入其他成员的文件属于根包
+    if let Some(name) = root.get("package").and_then(|p| p.get("name")).and_then(|n| n.as_str()) {
+        packages.push(Package { name: name.to_string(), path: String::new() });
+    }
+
+    packages
+}
+
+fn detect_node(repo: &Path, manifests: &[ManifestDir]) -> Vec<Package> {
+    let root: Option<serde_json::Value> = fs::read_to_string(repo.join("package.json"))
+        .ok()
+        .and_then(|c| serde_json::from_str(&c).ok());
+
+    // yarn/npm 使用 package.json 的 workspaces（数组或 { packages: [...] }），pnpm 使用 pnpm-workspace.yaml
+    let mut patterns: Vec<String> = root
+        .as_ref()
+        .and_then(|r| r.get("workspaces"))
+        .map(|w| w.get("packages").unwrap_or(w))
+        .and_then(|w| w.as_array())
+        .map(|items| items.iter().filter_map(|i| i.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+
+    if let Some(pnpm) = fs::read_to_string(repo.join("pnpm-workspace.yaml"))
+        .ok()
+        .and_then(|c| serde_yaml::from_str::<serde_yaml::Value>(&c).ok())
+    {
+        if let Some(items) = pnpm.get("packages").and_then(|p| p.as_sequence()) {
+            patterns.extend(items.iter().filter_map(|i| i.as_str().map(String::from)));
+        }
+    }
+
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+
+    workspace_members(manifests, "package.json", &patterns, &[])
+        .into_iter()
+        .filter_map(|dir| {
+            let content = fs::read_to_string(repo.join(dir).join("package.json")).ok()?;
+            let manifest: serde_json::Value = serde_json::from_str(&content).ok()?;
+            let name = manifest.get("name")?.as_str()?;
+            // "@org/ui" 作为 scope 时只取 "ui"
+            let name = name.rsplit('/').next().unwrap_or(name);
+            Some(Package { name: name.to_string(), path: dir.to_string() })
+        })
+        .collect()
+}
+
+fn detect_go(repo: &Path, manifests: &[ManifestDir]) -> Vec<Package> {
+    // 有 go.work 时只取其中 use 的模块，否则所有 go.mod 都视为独立模块
+    let used: Option<Vec<String>> = fs::read_to_string(repo.join("go.work")).ok().map(|content| {
+        let mut dirs = Vec::new();
+        let mut in_block = false;
+        for line in content.lines() {
+            let line = line.split("//").next().unwrap_or("").trim();
+            if in_block {
+                if line == ")" {
+                    in_block = false;
+                } else if !line.is_empty() {
+                    dirs.push(line.to_string());
+                }
+            } else if line == "use (" {
+                in_block = true;
+            } else if let Some(dir) = line.strip_prefix("use ") {
+                dirs.push(dir.trim().to_string());
+            }
+        }
+        dirs.into_iter()
+            .map(|d| d.trim_matches('"').trim_start_matches("./").trim_end_matches('/').to_string())
+            .map(|d| if d == "." { String::new() } else { d })
+            .collect()
+    });
+
+    manifests
+        .iter()
+        .filter(|m| m.file == "go.mod")
+        .filter(|m| used.as_ref().is_none_or(|dirs| dirs.contains(&m.dir)))
+        .filter_map(|m| {
+            let content = fs::read_to_string(repo.join(&m.dir).join("go.mod")).ok()?;
+            let module = content.lines().find_map(|l| l.trim().strip_prefix("module "))?;
+            let module = module.trim().trim_matches('"');
+            let name = module.rsplit('/').next().unwrap_or(module);
+            Some(Package { name: name.to_string(), path: m.dir.clone() })
+        })
+        .collect()
+}
+
+fn read_toml(path: &Path) -> Option<toml::Value> {
+    let content = fs::read_to_string(path).ok()?;
+    toml::from_str(&content).ok()
+}
@@ -0,0 +1,189 @@
+use colored::*;
+use crate::config::GcmSettings;
+use crate::git::git_output;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// 提交时附加的签名与 trailer 选项
+#[derive(Debug, Default, Clone)]
+pub struct CommitOptions {
+    pub sign: bool,
+    pub signing_key: Option<String>,
+    pub signoff: bool,
+    pub co_authors: Vec<String>,
+}
+
+impl CommitOptions {
+    /// 合并命令行参数与配置，命令行只能开启不能关闭配置项
+    pub fn resolve(
+        repo: &Path,
+        settings: &GcmSettings,
+        sign: bool,
+        signoff: bool,
+        co_authors: &[String],
+        pair: bool,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        let known = known_authors(repo, settings);
+
+        let mut resolved = Vec::new();
+        for author in co_authors {
+            let full = resolve_author(author, &known)?;
+            if !resolved.contains(&full) {
+                resolved.push(full);
+            }
+        }
+
+        if pair {
+            for full in select_pair_partners(&known)? {
+                if !resolved.contains(&full) {
+                    resolved.push(full);
+                }
+            }
+        }
+
+        Ok(CommitOptions {
+            sign: sign || settings.sign,
+            signing_key: settings.signing_key.clone(),
+            signoff: signoff || settings.signoff,
+            co_authors: resolved,
+        })
+    }
+
+    /// 追加到 `git commit` 的参数
+    pub fn commit_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.sign {
+            match &self.signing_key {
+                Some(key) => args.push(format!("-S{}", key)),
+                None => args.push("-S".to_string()),
+            }
+        }
+        if self.signoff {
+            args.push("--signoff".to_string());
+        }
+        args
+    }
+
+    /// 通过 `git interpret-trailers` 追加 Co-authored-by，沿用 git 的 trailer 解析与去重规则
+    pub fn apply_trailers(&self, repo: &Path, message: &str) -> Result<String, Box<dyn std::error::Error>> {
+        if self.co_authors.is_empty() {
+            return Ok(message.to_string());
+        }
+
+        let mut cmd = Command::new("git");
+        cmd.current_dir(repo)
+            .arg("interpret-trailers")
+            .arg("--if-exists")
+            .arg("addIfDifferent");
+        for author in &self.co_authors {
+            cmd.arg("--trailer").arg(format!("Co-authored-by: {}", author));
+        }
+
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        // 输入缺少结尾换行时 git 会把 trailer 直接接在正文最后一行之后
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(message.trim_end().as_bytes())?;
+            stdin.write_all(b"\n")?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(t!("trailers.interpret_failed", error = String::from_utf8_lossy(&output.stderr).trim()).into());
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).trim_end().to_string())
+    }
+}
+
+/// 配置中的结对伙伴 + 最近提交作者（排除自己），按出现顺序去重
+fn known_authors(repo: &Path, settings: &GcmSettings) -> Vec<String> {
+    let mut authors: Vec<String> = settings.co_authors.clone();
+
+    let me = git_output(repo, &["config", "user.email"])
+        .map(|s| s.trim().to_lowercase())
+        .unwrap_or_default();
+
+    if let Ok(log) = git_output(repo, &["log", "-n", "200", "--format=%an <%ae>"]) {
+        for line in log.lines() {
+            let line = line.trim();
+            if line.is_empty() || authors.iter().any(|a| a == line) {
+                continue;
+            }
+            if !me.is_empty() && line.to_lowercase().ends_with(&format!("<{}>", me)) {
+                continue;
+            }
+            authors.push(line.to_string());
+        }
+    }
+
+    authors
+}
+
+/// 完整的 "Name <email>" 原样使用，否则按名字或邮箱在已知作者中唯一匹配
+fn resolve_author(input: &str, known: &[String]) -> Result<String, Box<dyn std::error::Error>> {
+    if is_full_identity(input) {
+        return Ok(input.trim().to_string());
+    }
+
+    let needle = input.trim().to_lowercase();
+    let matches: Vec<&String> = known
+        .iter()
+        .filter(|a| a.to_lowercase().contains(&needle))
+        .collect();
+
+    match matches.len() {
+        1 => Ok(matches[0].clone()),
+        0 => Err(t!("trailers.author_not_found", input = input).into()),
+        _ => Err(t!(
+            "trailers.author_ambiguous",
+            input = input,
+            matches = matches.iter().map(|s| s.as_str()).collect::<Vec<_>>().join(", ")
+        )
+        .into()),
+    }
+}
+
+fn is_full_identity(s: &str) -> bool {
+    let s = s.trim();
+    match (s.find('<'), s.rfind('>')) {
+        (Some(start), Some(end)) => start > 0 && end == s.len() - 1 && s[start + 1..end].contains('@'),
+        _ => false,
+    }
+}
+
+fn select_pair_partners(known: &[String]) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    if known.is_empty() {
+        println!("{}", t!("trailers.no_partners").yellow());
+        return Ok(Vec::new());
+    }
+
+    println!("{}", t!("trailers.select_partners").cyan());
+    for (i, author) in known.iter().enumerate() {
+        println!("  {}. {}", i + 1, author);
+    }
+    print!("{}", t!("trailers.select_prompt"));
+    io::stdout().flush()?;
+
+    let mut response = String::new();
+    io::stdin().read_line(&mut response)?;
+
+    let mut selected = Vec::new();
+    for part in response.trim().split(|c: char| c == ',' || c.is_whitespace()) {
+        if part.is_empty() {
+            continue;
+        }
+        let index: usize = part.parse().map_err(|_| t!("common.invalid_number", value = part))?;
+        match known.get(index.wrapping_sub(1)) {
+            Some(author) => selected.push(author.clone()),
+            None => return Err(t!("common.number_out_of_range", value = index).into()),
+        }
+    }
+
+    Ok(selected)
+}
@@ -0,0 +1,273 @@
+use colored::*;
+use crate::commands::{display_width, pad};
+use crate::config::FileTypeManager;
+use crate::git::{current_branch, git_output};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::thread;
+
+use super::ignores::{stage_changes, IgnoreRules};
+use super::{analyze_git_changes, generate_message_from_changes, resolve_style, CommitOptions};
+
+/// 扫描时跳过的目录，避免深入依赖和构建产物
+pub(super) const SKIP_DIRS: &[&str] = &[".git", "node_modules", "target", "vendor"];
+
+struct RepoPlan {
+    path: PathBuf,
+    name: String,
+    branch: String,
+    change_count: usize,
+    message: String,
+    depth: usize,
+}
+
+pub fn run_workspace(dir: &str, commit_options: &CommitOptions, style: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+    let root = fs::canonicalize(dir)?;
+    println!("{} {}", t!("workspace.scanning").cyan(), root.display());
+
+    let mut repos = Vec::new();
+    find_git_repos(&root, &mut repos)?;
+    if repos.is_empty() {
+        return Err(t!("workspace.no_repos", dir = root.display()).into());
+    }
+    println!("{} {}", t!("workspace.found").cyan(), repos.len());
+
+    let file_type_manager = FileTypeManager::new()?;
+    let plans = analyze_repos(&root, &repos, &file_type_manager, style);
+
+    if plans.is_empty() {
+        println!("{}", t!("workspace.no_changes").green());
+        return Ok(());
+    }
+
+    display_plans(&plans);
+
+    let selected = select_plans(plans.len())?;
+    if selected.is_empty() {
+        println!("{}", t!("workspace.none_selected").yellow());
+        return Ok(());
+    }
+
+    let selected_plans: Vec<&RepoPlan> = selected.iter().map(|&i| &plans[i]).collect();
+    commit_plans(&selected_plans, commit_options)
+}
+
+/// 递归查找包含 `.git`（目录或子模块的 gitfile）的目录
+fn find_git_repos(dir: &Path, repos: &mut Vec<PathBuf>) -> Result<(), Box<dyn std::error::Error>> {
+    if dir.join(".git").exists() {
+        repos.push(dir.to_path_buf());
+    }
+
+    let mut entries: Vec<_> = fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
+    entries.sort_by_key(|e| e.file_name());
+
+    for entry in entries {
+        let file_type = match entry.file_type() {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        if !file_type.is_dir() {
+            continue;
+        }
+
+        let name = entry.file_name();
+        if SKIP_DIRS.iter().any(|s| name == *s) {
+            continue;
+        }
+
+        // 无权限等错误只跳过该目录，不中断整个扫描
+        let _ = find_git_repos(&entry.path(), repos);
+    }
+
+    Ok(())
+}
+
+fn analyze_repos(root: &Path, repos: &[PathBuf], file_type_manager: &FileTypeManager, style: Option<&str>) -> Vec<RepoPlan> {
+    let results: Vec<Option<RepoPlan>> = thread::scope(|scope| {
+        let handles: Vec<_> = repos
+            .iter()
+            .map(|repo| scope.spawn(move || analyze_repo(root, repo, file_type_manager, style)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|h| h.join().unwrap_or(None))
+            .collect()
+    });
+
+    results.into_iter().flatten().collect()
+}
+
+/// 每个仓库可以在 .cyber-zen.toml 中固定自己的提交风格
+fn analyze_repo(root: &Path, repo: &Path, file_type_manager: &FileTypeManager, style: Option<&str>) -> Option<RepoPlan> {
+    let analysis = resolve_style(repo, file_type_manager, style)
+        .and_then(|style| Ok((style, analyze_git_changes(repo, file_type_manager)?)));
+    let (style, changes) = match analysis {
+        Ok(c) => c,
+        Err(e) => {
+            println!("{} {} - {}", t!("workspace.analyze_failed").red(), repo.display(), e);
+            return None;
+        }
+    };
+
+    if changes.is_empty() {
+        return None;
+    }
+
+    let rel = repo.strip_prefix(root).unwrap_or(repo);
+    let name = if rel.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        rel.display().to_string()
+    };
+
+    Some(RepoPlan {
+        path: repo.to_path_buf(),
+        name,
+        branch: current_branch(repo),
+        change_count: changes.len(),
+        message: generate_message_from_changes(&changes, file_type_manager, style),
+        depth: rel.components().count(),
+    })
+}
+
+fn display_plans(plans: &[RepoPlan]) {
+    let headers = [
+        "#".to_string(),
+        t!("workspace.col_repo"),
+        t!("workspace.col_branch"),
+        t!("workspace.col_changes"),
+        t!("workspace.col_message"),
+    ];
+    let rows: Vec<[String; 5]> = plans
+        .iter()
+        .enumerate()
+        .map(|(i, p)| {
+            [
+                (i + 1).to_string(),
+                p.name.clone(),
+                p.branch.clone(),
+                p.change_count.to_string(),
+                p.message.lines().next().unwrap_or("").to_string(),
+            ]
+        })
+        .collect();
+
+    let mut widths = headers.each_ref().map(|h| display_width(h));
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row.iter()) {
+            *w = (*w).max(display_width(cell));
+        }
+    }
+
+    println!();
+    println!("{}", t!("workspace.overview").cyan());
+    let header_line: Vec<String> = headers
+        .iter()
+        .zip(widths.iter())
+        .map(|(h, &w)| pad(h, w))
+        .collect();
+    println!("  {}", header_line.join("  ").yellow());
+
+    for row in &rows {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, &w)| pad(cell, w))
+            .collect();
+        println!("  {}", line.join("  "));
+    }
+    println!();
+}
+
+fn select_plans(count: usize) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    print!("{}", t!("workspace.select_prompt"));
+    io::stdout().flush()?;
+
+    let mut response = String::new();
+    io::stdin().read_line(&mut response)?;
+    parse_selection(response.trim(), count)
+}
+
+fn parse_selection(input: &str, count: usize) -> Result<Vec<usize>, Box<dyn std::error::Error>> {
+    let input = input.to_lowercase();
+    if input.is_empty() || input == "a" || input == "all" {
+        return Ok((0..count).collect());
+    }
+    if input == "n" || input == "no" {
+        return Ok(Vec::new());
+    }
+
+    let mut selected = Vec::new();
+    for part in input.split(|c: char| c == ',' || c.is_whitespace()) {
+        if part.is_empty() {
+            continue;
+        }
+        let index: usize = part
+            .parse()
+            .map_err(|_| t!("common.invalid_number", value = part))?;
+        if index == 0 || index > count {
+            return Err(t!("common.number_out_of_range", value = index).into());
+        }
+        if !selected.contains(&(index - 1)) {
+            selected.push(index - 1);
+        }
+    }
+
+    Ok(selected)
+}
+
+/// 按目录深度从深到浅分批提交，保证子模块先于父仓库提交，父仓库才能记录最新的子模块指针
+fn commit_plans(plans: &[&RepoPlan], commit_options: &CommitOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let mut depths: Vec<usize> = plans.iter().map(|p| p.depth).collect();
+    depths.sort_unstable();
+    depths.dedup();
+
+    let mut failures = Vec::new();
+
+    for depth in depths.into_iter().rev() {
+        let batch: Vec<&RepoPlan> = plans.iter().filter(|p| p.depth == depth).copied().collect();
+
+        let results: Vec<(&RepoPlan, Result<(), String>)> = thread::scope(|scope| {
+            let handles: Vec<_> = batch
+                .iter()
+                .map(|&plan| (plan, scope.spawn(move || commit_and_push(plan, commit_options).map_err(|e| e.to_string()))))
+                .collect();
+
+            handles
+                .into_iter()
+                .map(|(plan, h)| (plan, h.join().unwrap_or_else(|_| Err(t!("workspace.thread_panicked")))))
+                .collect()
+        });
+
+        for (plan, result) in results {
+            match result {
+                Ok(()) => println!("{} {}", t!("workspace.repo_done").green(), plan.name),
+                Err(e) => {
+                    println!("{} {} - {}", t!("workspace.repo_failed").red(), plan.name, e);
+                    failures.push(plan.name.clone());
+                }
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        return Err(t!("workspace.failures", count = failures.len(), repos = failures.join(", ")).into());
+    }
+
+    println!("{}", t!("workspace.done").green());
+    Ok(())
+}
+
+fn commit_and_push(plan: &RepoPlan, commit_options: &CommitOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let message = commit_options.apply_trailers(&plan.path, &plan.message)?;
+    let extra_args = commit_options.commit_args();
+    let mut commit_args = vec!["commit", "-m", &message, "--no-verify"];
+    commit_args.extend(extra_args.iter().map(|s| s.as_str()));
+
+    stage_changes(&plan.path, &IgnoreRules::load(&plan.path)?)?;
+    git_output(&plan.path, &commit_args)?;
+    git_output(&plan.path, &["push"])?;
+    Ok(())
+}
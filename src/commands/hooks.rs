@@ -0,0 +1,256 @@
+use colored::*;
+use crate::commands::gcm::{analyze_staged_changes, generate_message_from_changes, resolve_style};
+use crate::config::FileTypeManager;
+use crate::git::{check_git_repo, git_output};
+use regex::Regex;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// 写入 hook 文件的标记，用于识别由本工具管理的 hook
+const HOOK_MARKER: &str = "# managed-by: cyber-zen";
+
+const HOOK_TEMPLATE: &str = r#"#!/bin/sh
+{marker}
+if command -v cyber-zen >/dev/null 2>&1; then
+    CYBER_ZEN=cyber-zen
+else
+    CYBER_ZEN='{fallback}'
+fi
+[ -x "$(command -v "$CYBER_ZEN")" ] || exit 0
+exec "$CYBER_ZEN" hooks run {hook} "$@"
+"#;
+
+const PREPARE_COMMIT_MSG: &str = "prepare-commit-msg";
+const COMMIT_MSG: &str = "commit-msg";
+
+pub fn run_hooks_install(commit_msg: bool, hooks_path: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let hooks_dir = resolve_hooks_dir(repo, hooks_path.as_deref())?;
+    if hooks_path.is_some() {
+        switch_hooks_dir(repo, &hooks_dir)?;
+    }
+    fs::create_dir_all(&hooks_dir)?;
+    println!("{} {}", t!("hooks.dir").cyan(), hooks_dir.display());
+
+    let mut hooks = vec![PREPARE_COMMIT_MSG];
+    if commit_msg {
+        hooks.push(COMMIT_MSG);
+    }
+
+    for hook in hooks {
+        let hook_path = hooks_dir.join(hook);
+
+        if hook_path.exists() && !is_managed_hook(&hook_path) {
+            println!("{} {}", t!("hooks.skipped").yellow(), t!("hooks.foreign_hook", path = hook_path.display()));
+            continue;
+        }
+
+        fs::write(&hook_path, hook_script(hook))?;
+        set_executable(&hook_path)?;
+        println!("{} {}", t!("hooks.installed").green(), hook_path.display());
+    }
+
+    Ok(())
+}
+
+pub fn run_hooks_uninstall(hooks_path: Option<String>) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let hooks_dir = resolve_hooks_dir(repo, hooks_path.as_deref())?;
+
+    for hook in [PREPARE_COMMIT_MSG, COMMIT_MSG] {
+        let hook_path = hooks_dir.join(hook);
+        if !hook_path.exists() {
+            continue;
+        }
+
+        if !is_managed_hook(&hook_path) {
+            println!("{} {}", t!("hooks.kept").yellow(), t!("hooks.not_managed", path = hook_path.display()));
+            continue;
+        }
+
+        fs::remove_file(&hook_path)?;
+        println!("{} {}", t!("hooks.removed").green(), hook_path.display());
+    }
+
+    Ok(())
+}
+
+/// hook 脚本的入口，参数与 git 传给 hook 的参数一致
+pub fn run_hook(hook: String, args: Vec<String>) -> Result<(), Box<dyn std::error::Error>> {
+    match hook.as_str() {
+        PREPARE_COMMIT_MSG => {
+            // 生成失败不应阻止用户提交
+            if let Err(e) = prepare_commit_msg(&args) {
+                eprintln!("{} {}", t!("hooks.generate_failed").yellow(), e);
+            }
+            Ok(())
+        }
+        COMMIT_MSG => check_commit_msg(&args),
+        _ => Err(t!("hooks.unknown", hook = hook).into()),
+    }
+}
+
+/// 未指定 `--hooks-path` 时使用 git 实际生效的目录（会遵循已配置的 core.hooksPath）；只读取，不修改配置
+fn resolve_hooks_dir(repo: &Path, hooks_path: Option<&str>) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    match hooks_path {
+        Some(dir) => Ok(PathBuf::from(dir)),
+        None => active_hooks_dir(repo),
+    }
+}
+
+fn active_hooks_dir(repo: &Path) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let dir = git_output(repo, &["rev-parse", "--git-path", "hooks"])?;
+    Ok(PathBuf::from(dir.trim()))
+}
+
+/// 把 core.hooksPath 指向新目录；当前目录中有其他工具的 hook 时拒绝切换，否则它们会被悄悄停用
+fn switch_hooks_dir(repo: &Path, hooks_dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let active = active_hooks_dir(repo)?;
+    if same_dir(&active, hooks_dir) {
+        return Ok(());
+    }
+
+    let foreign: Vec<String> = hook_files(&active)
+        .into_iter()
+        .filter(|path| !is_managed_hook(path))
+        .map(|path| path.display().to_string())
+        .collect();
+    if !foreign.is_empty() {
+        return Err(t!("hooks.foreign_in_active", dir = active.display(), hooks = foreign.join(", ")).into());
+    }
+
+    let dir = hooks_dir.to_string_lossy();
+    git_output(repo, &["config", "core.hooksPath", &dir])?;
+    Ok(())
+}
+
+/// 目录中会被 git 执行的 hook 文件，忽略 `.sample` 示例
+fn hook_files(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file() && path.extension().is_none_or(|ext| ext != "sample"))
+        .collect();
+    files.sort();
+    files
+}
+
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+fn is_managed_hook(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|content| content.contains(HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+fn hook_script(hook: &str) -> String {
+    // 优先使用 PATH 中的 cyber-zen，找不到时回退到安装 hook 时的可执行文件
+    let fallback = std::env::current_exe()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "cyber-zen".to_string());
+
+    HOOK_TEMPLATE
+        .replace("{marker}", HOOK_MARKER)
+        .replace("{fallback}", &fallback)
+        .replace("{hook}", hook)
+}
+
+#[cfg(unix)]
+fn set_executable(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mut perms = fs::metadata(path)?.permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn set_executable(_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    Ok(())
+}
+
+/// 仅在普通 `git commit`（无 -m/-F、非 merge/squash/amend）时预填提交信息
+fn prepare_commit_msg(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let msg_file = args.first().ok_or_else(|| t!("hooks.missing_file"))?;
+    let source = args.get(1).map(|s| s.as_str()).unwrap_or("");
+
+    if !source.is_empty() && source != "template" {
+        return Ok(());
+    }
+
+    let repo = Path::new(".");
+    let file_type_manager = FileTypeManager::new()?;
+    let changes = analyze_staged_changes(repo, &file_type_manager)?;
+    if changes.is_empty() {
+        return Ok(());
+    }
+
+    let style = resolve_style(repo, &file_type_manager, None)?;
+    let message = generate_message_from_changes(&changes, &file_type_manager, style);
+    let existing = fs::read_to_string(msg_file).unwrap_or_default();
+
+    fs::write(msg_file, format!("{}\n{}", message, existing))?;
+    Ok(())
+}
+
+/// 按当前提交风格校验提交信息首行，带类型的风格要求 `type(scope): summary` 格式
+fn check_commit_msg(args: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    let msg_file = args.first().ok_or_else(|| t!("hooks.missing_file"))?;
+    let content = fs::read_to_string(msg_file)?;
+
+    let header = content
+        .lines()
+        .find(|line| !line.starts_with('#') && !line.trim().is_empty())
+        .unwrap_or("");
+
+    // git 自动生成的提交不做校验
+    if header.starts_with("Merge ") || header.starts_with("Revert ") || header.starts_with("fixup! ") || header.starts_with("squash! ") {
+        return Ok(());
+    }
+
+    let file_type_manager = FileTypeManager::new()?;
+    let style = resolve_style(Path::new("."), &file_type_manager, None)?;
+
+    // 不带类型前缀的风格（如 sentence）只要求首行非空
+    if !style.has_type() {
+        if header.is_empty() {
+            eprintln!("{}", t!("hooks.empty_message").red());
+            return Err(t!("hooks.check_failed").into());
+        }
+        return Ok(());
+    }
+
+    // 允许 gitmoji 等风格在类型前带一个 emoji
+    let re = Regex::new(r"^(?:[^\x00-\x7F]+\s+)?([a-z]+)(\([^)]+\))?!?: \S")?;
+    let valid = re
+        .captures(header)
+        .map(|caps| file_type_manager.is_commit_type(&caps[1]))
+        .unwrap_or(false);
+
+    if !valid {
+        eprintln!("{} {}", t!("hooks.invalid_message").red(), header);
+        eprintln!(
+            "  {}",
+            t!(
+                "hooks.expected_format",
+                format = style.render_header("type", Some("scope"), "summary"),
+                example = style.render_header("feat", Some("api"), "add user endpoint")
+            )
+        );
+        return Err(t!("hooks.check_failed").into());
+    }
+
+    Ok(())
+}
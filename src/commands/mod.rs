@@ -1,6 +1,25 @@
+pub mod changelog;
+pub mod classify;
 pub mod gcm;
+pub mod hooks;
 pub mod compress;
+pub mod release;
 pub mod server;
 pub mod status;
+pub mod sync;
 pub mod uninstall;
+pub mod wip;
+
+/// 终端显示宽度：中日韩等全角字符按 2 列计算
+pub(crate) fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| if (c as u32) >= 0x1100 { 2 } else { 1 })
+        .sum()
+}
+
+/// 按显示宽度右侧补空格，用于对齐表格列
+pub(crate) fn pad(s: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(s));
+    format!("{}{}", s, " ".repeat(padding))
+}
 
@@ -0,0 +1,414 @@
+use colored::*;
+use clap::ValueEnum;
+use crate::commands::changelog::{collect_commits, render_section, write_changelog};
+use crate::config::FileTypeManager;
+use crate::git::{check_git_repo, exec_git_command, git_output};
+use regex::Regex;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum BumpLevel {
+    Patch,
+    Minor,
+    Major,
+}
+
+/// release 命令行参数
+pub struct ReleaseOptions {
+    pub bump: Option<BumpLevel>,
+    pub pre: Option<String>,
+    pub tag_prefix: String,
+    pub changelog: bool,
+    pub push: bool,
+    pub remote: String,
+    pub dry_run: bool,
+    pub yes: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Version {
+    major: u64,
+    minor: u64,
+    patch: u64,
+    pre: Option<String>,
+}
+
+impl Version {
+    fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_start_matches('v');
+        let (core, pre) = match s.split_once('-') {
+            Some((core, pre)) => (core, Some(pre.to_string())),
+            None => (s, None),
+        };
+        // 忽略构建元数据
+        let core = core.split('+').next()?;
+
+        let mut parts = core.split('.');
+        let version = Version {
+            major: parts.next()?.parse().ok()?,
+            minor: parts.next()?.parse().ok()?,
+            patch: parts.next()?.parse().ok()?,
+            pre,
+        };
+        if parts.next().is_some() {
+            return None;
+        }
+        Some(version)
+    }
+
+    fn bump(&self, level: BumpLevel) -> Self {
+        match level {
+            BumpLevel::Major => Version { major: self.major + 1, minor: 0, patch: 0, pre: None },
+            BumpLevel::Minor => Version { major: self.major, minor: self.minor + 1, patch: 0, pre: None },
+            BumpLevel::Patch => Version { major: self.major, minor: self.minor, patch: self.patch + 1, pre: None },
+        }
+    }
+
+    /// 预发布版本本身已包含的升级级别：x.0.0 为 major，x.y.0 为 minor，其余为 patch
+    fn implied_level(&self) -> BumpLevel {
+        match (self.minor, self.patch) {
+            (0, 0) => BumpLevel::Major,
+            (_, 0) => BumpLevel::Minor,
+            _ => BumpLevel::Patch,
+        }
+    }
+
+    /// 当前已是同标识的预发布版本且升级级别未超出时只递增序号，例如 1.2.0-rc.1 → 1.2.0-rc.2；
+    /// 级别超出时重新计算基础版本，例如 1.3.0-rc.1 遇到破坏性变更 → 2.0.0-rc.1
+    fn next(&self, level: BumpLevel, pre: Option<&str>) -> Self {
+        match (&self.pre, pre) {
+            (Some(current), Some(id)) => {
+                let base = Version { pre: None, ..self.clone() };
+                let target = if level <= self.implied_level() { base.clone() } else { base.bump(level) };
+                let number = current
+                    .strip_prefix(id)
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .and_then(|n| n.parse::<u64>().ok());
+                match number {
+                    Some(n) if target == base => Version { pre: Some(format!("{}.{}", id, n + 1)), ..target },
+                    _ => Version { pre: Some(format!("{}.1", id)), ..target },
+                }
+            }
+            // 从预发布版本正式发布时去掉预发布标识，级别超出时同样重新计算
+            (Some(_), None) => {
+                let base = Version { pre: None, ..self.clone() };
+                if level <= self.implied_level() { base } else { base.bump(level) }
+            }
+            (None, Some(id)) => Version { pre: Some(format!("{}.1", id)), ..self.bump(level) },
+            (None, None) => self.bump(level),
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = &self.pre {
+            write!(f, "-{}", pre)?;
+        }
+        Ok(())
+    }
+}
+
+pub fn run_release(options: ReleaseOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let status = git_output(repo, &["status", "--porcelain"])?;
+    if !status.trim().is_empty() && !options.dry_run {
+        return Err(t!("release.dirty").into());
+    }
+
+    let last_tag = git_output(repo, &["describe", "--tags", "--abbrev=0", "HEAD"])
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+
+    let current = match &last_tag {
+        Some(tag) => Version::parse(tag.trim_start_matches(options.tag_prefix.as_str()))
+            .ok_or_else(|| t!("release.invalid_tag", tag = tag))?,
+        None => read_manifest_version(repo).unwrap_or(Version { major: 0, minor: 0, patch: 0, pre: None }),
+    };
+
+    // 预发布转正式版时，提交范围从上一个正式版本算起
+    let promoting = current.pre.is_some() && options.pre.is_none();
+    let base_tag = if promoting {
+        last_stable_tag(repo, &options.tag_prefix)
+    } else {
+        last_tag.clone()
+    };
+
+    let (mut commits, _) = collect_commits(repo, base_tag.as_deref(), "HEAD")?;
+    // 之前的预发布提交不计入变更
+    commits.retain(|c| !(c.commit_type == "chore" && c.scope.as_deref() == Some("release")));
+    let range = match &base_tag {
+        Some(tag) => format!("{}..HEAD", tag),
+        None => "HEAD".to_string(),
+    };
+    let commit_count = git_output(repo, &["rev-list", "--count", &range])?;
+    if commit_count.trim() == "0" && !promoting {
+        let since = last_tag.clone().unwrap_or_else(|| t!("release.initial_commit"));
+        return Err(t!("release.no_commits", since = since).into());
+    }
+
+    let detected = if commits.iter().any(|c| c.breaking) {
+        BumpLevel::Major
+    } else if commits.iter().any(|c| c.commit_type == "feat") {
+        BumpLevel::Minor
+    } else {
+        BumpLevel::Patch
+    };
+    let level = options.bump.unwrap_or(detected);
+
+    let next = current.next(level, options.pre.as_deref());
+    let tag = format!("{}{}", options.tag_prefix, next);
+
+    println!("{} {}", t!("release.previous").cyan(), last_tag.clone().unwrap_or_else(|| t!("release.none")));
+    println!(
+        "{} {}",
+        t!("release.new_commits").cyan(),
+        t!("release.commit_count", count = commit_count.trim(), conventional = commits.len())
+    );
+    println!("{} {:?}", t!("release.level").cyan(), level);
+    println!("{} {} → {}", t!("release.version").cyan(), current, next.to_string().green());
+
+    let edits = plan_manifest_edits(repo, &next)?;
+    for (path, _) in &edits {
+        println!("  {} {}", t!("release.bump_file").yellow(), path);
+    }
+    if options.changelog {
+        println!("  {} CHANGELOG.md", t!("release.changelog").yellow());
+    }
+
+    let message = format!("chore(release): {}", tag);
+    println!("{} {}", t!("gcm.message").cyan(), message);
+    println!("{} {}", t!("release.tag").cyan(), tag);
+    if options.push {
+        println!("{} {}", t!("release.push_to").cyan(), options.remote);
+    }
+
+    if options.dry_run {
+        println!("{}", t!("release.dry_run").yellow());
+        return Ok(());
+    }
+
+    if !options.yes && !confirm(&t!("release.confirm"))? {
+        return Err(t!("common.cancelled").into());
+    }
+
+    for (path, content) in &edits {
+        fs::write(repo.join(path), content)?;
+    }
+
+    let file_type_manager = FileTypeManager::new()?;
+    let section = render_section(repo, &file_type_manager, &tag, base_tag.as_deref(), "HEAD", &commits);
+    if options.changelog {
+        write_changelog(&repo.join("CHANGELOG.md"), &tag, &section, true)?;
+    }
+
+    let mut paths: Vec<&str> = edits.iter().map(|(p, _)| p.as_str()).collect();
+    if options.changelog {
+        paths.push("CHANGELOG.md");
+    }
+    if !paths.is_empty() {
+        let mut add_args = vec!["add", "--"];
+        add_args.extend(paths);
+        exec_git_command(repo, &add_args)?;
+        exec_git_command(repo, &["commit", "-m", &message, "--no-verify"])?;
+        println!("{}", t!("release.committed").green());
+    }
+
+    // 标签说明使用本次版本的变更日志；默认的 strip 会把 `#` 开头的标题当作注释删掉
+    exec_git_command(repo, &["tag", "-a", "--cleanup=verbatim", &tag, "-m", &section])?;
+    println!("{} {}", t!("release.tagged").green(), tag);
+
+    if options.push {
+        exec_git_command(repo, &["push", &options.remote, "HEAD"])?;
+        exec_git_command(repo, &["push", &options.remote, &tag])?;
+        println!("{}", t!("release.pushed").green());
+    }
+
+    println!("{} {}", t!("release.done").green(), tag);
+    Ok(())
+}
+
+/// HEAD 可达的标签中版本最高的正式版本
+fn last_stable_tag(repo: &Path, prefix: &str) -> Option<String> {
+    let tags = git_output(repo, &["tag", "--merged", "HEAD", "--list", &format!("{}*", prefix)]).ok()?;
+
+    tags.lines()
+        .filter_map(|tag| {
+            let version = Version::parse(tag.strip_prefix(prefix)?)?;
+            version.pre.is_none().then_some(((version.major, version.minor, version.patch), tag.to_string()))
+        })
+        .max()
+        .map(|(_, tag)| tag)
+}
+
+fn confirm(prompt: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    print!("{}", prompt);
+    io::stdout().flush()?;
+
+    let mut response = String::new();
+    io::stdin().read_line(&mut response)?;
+
+    let response = response.trim().to_lowercase();
+    Ok(response.is_empty() || response == "y" || response == "yes")
+}
+
+/// 没有标签时从清单文件中读取当前版本
+fn read_manifest_version(repo: &Path) -> Option<Version> {
+    for (file, section) in [("Cargo.toml", Some("package")), ("package.json", None), ("pyproject.toml", Some("project"))] {
+        let Ok(content) = fs::read_to_string(repo.join(file)) else {
+            continue;
+        };
+        let version = match section {
+            Some(section) => find_toml_version(&content, &[section]).map(|(_, v)| v),
+            None => find_json_version(&content).map(|(_, v)| v),
+        };
+        if let Some(v) = version.and_then(|v| Version::parse(&v)) {
+            return Some(v);
+        }
+    }
+    None
+}
+
+/// 计算各清单文件更新后的内容，只改动版本号所在的行以保留原有格式
+fn plan_manifest_edits(repo: &Path, next: &Version) -> Result<Vec<(String, String)>, Box<dyn std::error::Error>> {
+    let new_version = next.to_string();
+    let mut edits = Vec::new();
+
+    if let Ok(content) = fs::read_to_string(repo.join("Cargo.toml")) {
+        if let Some((range, old)) = find_toml_version(&content, &["package", "workspace.package"]) {
+            let mut updated = content.clone();
+            updated.replace_range(range, &new_version);
+            edits.push(("Cargo.toml".to_string(), updated));
+
+            // 同步 Cargo.lock 中本包的版本，避免发布后工作区变脏
+            if let (Some(name), Ok(lock)) = (find_toml_string(&content, "package", "name"), fs::read_to_string(repo.join("Cargo.lock"))) {
+                let pattern = format!(
+                    r#"(?m)^name = "{}"\r?\nversion = "{}""#,
+                    regex::escape(&name),
+                    regex::escape(&old)
+                );
+                let re = Regex::new(&pattern)?;
+                if re.is_match(&lock) {
+                    let replacement = format!("name = \"{}\"\nversion = \"{}\"", name, new_version);
+                    edits.push(("Cargo.lock".to_string(), re.replace(&lock, replacement.as_str()).to_string()));
+                }
+            }
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(repo.join("package.json")) {
+        if let Some((range, _)) = find_json_version(&content) {
+            let mut updated = content.clone();
+            updated.replace_range(range, &new_version);
+            edits.push(("package.json".to_string(), updated));
+        }
+    }
+
+    if let Ok(content) = fs::read_to_string(repo.join("pyproject.toml")) {
+        if let Some((range, _)) = find_toml_version(&content, &["project", "tool.poetry"]) {
+            let mut updated = content.clone();
+            updated.replace_range(range, &new_version);
+            edits.push(("pyproject.toml".to_string(), updated));
+        }
+    }
+
+    Ok(edits)
+}
+
+/// 在指定的 TOML 表中查找 `version = "..."`，返回版本字符串在原文中的位置
+fn find_toml_version(content: &str, sections: &[&str]) -> Option<(std::ops::Range<usize>, String)> {
+    let re = Regex::new(r#"^\s*version\s*=\s*"([^"]*)""#).ok()?;
+    find_in_toml_sections(content, sections, &re)
+}
+
+fn find_toml_string(content: &str, section: &str, key: &str) -> Option<String> {
+    let re = Regex::new(&format!(r#"^\s*{}\s*=\s*"([^"]*)""#, regex::escape(key))).ok()?;
+    find_in_toml_sections(content, &[section], &re).map(|(_, v)| v)
+}
+
+fn find_in_toml_sections(content: &str, sections: &[&str], re: &Regex) -> Option<(std::ops::Range<usize>, String)> {
+    let mut current = String::new();
+    let mut offset = 0;
+
+    for line in content.split_inclusive('\n') {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && !trimmed.starts_with("[[") {
+            current = trimmed.trim_matches(|c| c == '[' || c == ']').trim().to_string();
+        } else if sections.contains(&current.as_str()) {
+            if let Some(caps) = re.captures(line) {
+                let m = caps.get(1)?;
+                return Some((offset + m.start()..offset + m.end(), m.as_str().to_string()));
+            }
+        }
+        offset += line.len();
+    }
+
+    None
+}
+
+/// package.json 顶层的 "version" 字段（第一层缩进）
+fn find_json_version(content: &str) -> Option<(std::ops::Range<usize>, String)> {
+    let value: serde_json::Value = serde_json::from_str(content).ok()?;
+    let version = value.get("version")?.as_str()?.to_string();
+
+    let re = Regex::new(&format!(r#""version"\s*:\s*"({})""#, regex::escape(&version))).ok()?;
+    let m = re.captures(content)?.get(1)?;
+    Some((m.start()..m.end(), version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn parse_accepts_prefix_pre_release_and_build_metadata() {
+        assert_eq!(version("v1.2.3"), Version { major: 1, minor: 2, patch: 3, pre: None });
+        assert_eq!(version("1.2.3-rc.1").pre.as_deref(), Some("rc.1"));
+        assert_eq!(version("1.2.3+build.5"), version("1.2.3"));
+    }
+
+    #[test]
+    fn parse_rejects_incomplete_versions() {
+        assert!(Version::parse("1.2").is_none());
+        assert!(Version::parse("1.2.3.4").is_none());
+        assert!(Version::parse("latest").is_none());
+    }
+
+    #[test]
+    fn next_bumps_stable_versions() {
+        assert_eq!(version("1.2.3").next(BumpLevel::Patch, None).to_string(), "1.2.4");
+        assert_eq!(version("1.2.3").next(BumpLevel::Minor, None).to_string(), "1.3.0");
+        assert_eq!(version("1.2.3").next(BumpLevel::Major, None).to_string(), "2.0.0");
+        assert_eq!(version("1.2.3").next(BumpLevel::Minor, Some("rc")).to_string(), "1.3.0-rc.1");
+    }
+
+    #[test]
+    fn next_increments_pre_release_within_level() {
+        assert_eq!(version("1.3.0-rc.1").next(BumpLevel::Minor, Some("rc")).to_string(), "1.3.0-rc.2");
+        assert_eq!(version("1.3.0-rc.1").next(BumpLevel::Patch, Some("rc")).to_string(), "1.3.0-rc.2");
+        assert_eq!(version("1.3.0-beta.2").next(BumpLevel::Patch, Some("rc")).to_string(), "1.3.0-rc.1");
+    }
+
+    #[test]
+    fn next_rebases_pre_release_when_level_grows() {
+        assert_eq!(version("1.3.0-rc.1").next(BumpLevel::Major, Some("rc")).to_string(), "2.0.0-rc.1");
+        assert_eq!(version("1.2.1-rc.3").next(BumpLevel::Minor, Some("rc")).to_string(), "1.3.0-rc.1");
+    }
+
+    #[test]
+    fn next_promotes_pre_release() {
+        assert_eq!(version("1.3.0-rc.2").next(BumpLevel::Minor, None).to_string(), "1.3.0");
+        assert_eq!(version("1.3.0-rc.2").next(BumpLevel::Major, None).to_string(), "2.0.0");
+    }
+}
@@ -9,30 +9,30 @@ pub fn run_server(dir: Option<String>, port: u16) -> Result<(), Box<dyn std::err
     let server_dir = dir.unwrap_or_else(|| "./".to_string());
     
     if !Path::new(&server_dir).exists() {
-        eprintln!("❌ 错误: 目录 '{}' 不存在", server_dir);
+        eprintln!("❌ {}", t!("server.dir_missing", dir = server_dir));
         std::process::exit(1);
     }
     
     let abs_path = fs::canonicalize(&server_dir)?;
     
     if port < 1 {
-        eprintln!("❌ 错误: 端口号必须在 1-65535 范围内");
+        eprintln!("❌ {}", t!("server.invalid_port"));
         std::process::exit(1);
     }
     
     if is_port_in_use(port) {
-        eprintln!("❌ 错误: 端口 {} 已被占用", port);
+        eprintln!("❌ {}", t!("server.port_in_use", port = port));
         std::process::exit(1);
     }
     
-    println!("🚀 启动静态文件服务器...");
-    println!("📁 服务目录: {}", abs_path.display());
-    println!("🌐 服务地址: http://localhost:{}", port);
-    println!("📋 按 Ctrl+C 停止服务器\n");
+    println!("🚀 {}", t!("server.starting"));
+    println!("📁 {}", t!("server.dir", dir = abs_path.display()));
+    println!("🌐 {}", t!("server.url", port = port));
+    println!("📋 {}\n", t!("server.stop_hint"));
     
     let addr = format!("0.0.0.0:{}", port);
     let listener = TcpListener::bind(&addr)?;
-    println!("✅ 服务器已启动，监听端口 {}", port);
+    println!("✅ {}", t!("server.started", port = port));
     
     for stream in listener.incoming() {
         match stream {
@@ -43,7 +43,7 @@ pub fn run_server(dir: Option<String>, port: u16) -> Result<(), Box<dyn std::err
                 });
             }
             Err(e) => {
-                eprintln!("连接错误: {}", e);
+                eprintln!("{}", t!("server.connection_error", error = e));
             }
         }
     }
@@ -0,0 +1,240 @@
+use colored::*;
+use crate::commands::changelog::{collect_commits, group_by_type, type_heading};
+use crate::config::{load_settings, FileTypeManager, SyncStrategy};
+use crate::git::{check_git_repo, current_branch, exec_git_command, git_output};
+use crate::i18n::ui_lang;
+use std::path::Path;
+
+pub struct SyncOptions {
+    pub rebase: bool,
+    pub merge: bool,
+    pub no_push: bool,
+}
+
+/// 拉取上游 → rebase/merge → 推送，与 gcm 组成日常的提交循环
+pub fn run_sync(options: SyncOptions) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let settings = load_settings(repo)?.sync;
+    let strategy = if options.merge {
+        SyncStrategy::Merge
+    } else if options.rebase {
+        SyncStrategy::Rebase
+    } else {
+        settings.strategy
+    };
+    let push = settings.push && !options.no_push;
+
+    if let Some(operation) = operation_in_progress(repo) {
+        return Err(t!("sync.in_progress", operation = operation).into());
+    }
+
+    let branch = current_branch(repo);
+    if branch == "HEAD" {
+        return Err(t!("sync.detached").into());
+    }
+
+    let dirty = !git_output(repo, &["status", "--porcelain"])?.trim().is_empty();
+    if dirty && !settings.autostash {
+        return Err(t!("sync.dirty").into());
+    }
+
+    let upstream = git_output(repo, &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    let remote = git_output(repo, &["config", &format!("branch.{}.remote", branch)])
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| "origin".to_string());
+
+    println!("{} {}", t!("sync.fetching").yellow(), remote);
+    exec_git_command(repo, &["fetch", &remote])?;
+
+    // 没有上游分支时只能推送并建立跟踪关系
+    let Some(upstream) = upstream else {
+        if !push {
+            println!("{}", t!("sync.no_upstream").yellow());
+            return Ok(());
+        }
+        println!("{}", t!("sync.set_upstream", remote = remote, branch = branch).yellow());
+        exec_git_command(repo, &["push", "-u", &remote, &branch])?;
+        println!("{}", t!("sync.done").green());
+        return Ok(());
+    };
+
+    let incoming = count_commits(repo, &format!("HEAD..{}", upstream))?;
+    let outgoing = count_commits(repo, &format!("{}..HEAD", upstream))?;
+    println!(
+        "{} {} ({})",
+        t!("sync.upstream").cyan(),
+        upstream,
+        t!("sync.ahead_behind", incoming = incoming, outgoing = outgoing)
+    );
+
+    let file_type_manager = FileTypeManager::new()?;
+
+    if incoming > 0 {
+        show_incoming(repo, &file_type_manager, &upstream)?;
+        integrate(repo, &file_type_manager, strategy, &upstream, dirty)?;
+    } else {
+        println!("{}", t!("sync.up_to_date").green());
+    }
+
+    if push && outgoing > 0 {
+        println!("{}", t!("gcm.run_push").yellow());
+        exec_git_command(repo, &["push", &remote, &format!("HEAD:{}", upstream_branch(&upstream, &remote))])?;
+        println!("{}", t!("gcm.push_done").green());
+    }
+
+    println!("{}", t!("sync.done").green());
+    Ok(())
+}
+
+fn count_commits(repo: &Path, range: &str) -> Result<usize, Box<dyn std::error::Error>> {
+    Ok(git_output(repo, &["rev-list", "--count", range])?.trim().parse().unwrap_or(0))
+}
+
+/// "origin/feature/x" → "feature/x"
+fn upstream_branch<'a>(upstream: &'a str, remote: &str) -> &'a str {
+    upstream
+        .strip_prefix(remote)
+        .and_then(|s| s.strip_prefix('/'))
+        .unwrap_or(upstream)
+}
+
+/// 上次同步中断后，需要先完成或放弃才能再次同步
+fn operation_in_progress(repo: &Path) -> Option<&'static str> {
+    let exists = |name: &str| {
+        git_output(repo, &["rev-parse", "--git-path", name])
+            .map(|path| repo.join(path.trim()).exists())
+            .unwrap_or(false)
+    };
+
+    if exists("rebase-merge") || exists("rebase-apply") {
+        Some("rebase")
+    } else if exists("MERGE_HEAD") {
+        Some("merge")
+    } else {
+        None
+    }
+}
+
+/// 按类型列出即将拉取的规范提交
+fn show_incoming(repo: &Path, file_type_manager: &FileTypeManager, upstream: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let (commits, skipped) = collect_commits(repo, Some("HEAD"), upstream)?;
+
+    println!();
+    println!("{}", t!("sync.incoming").cyan());
+    for (commit_type, group) in group_by_type(&commits) {
+        println!("  {}", type_heading(file_type_manager, ui_lang(), commit_type).yellow());
+        for commit in group {
+            let scope = commit.scope.as_deref().map(|s| format!("{}: ", s)).unwrap_or_default();
+            let marker = if commit.breaking { "⚠ ".red().to_string() } else { String::new() };
+            println!("    {}{}{} ({}, {})", marker, scope, commit.summary, commit.short_hash, commit.author);
+        }
+    }
+    if skipped > 0 {
+        println!("  {}", t!("sync.other_commits", count = skipped));
+    }
+    println!();
+
+    Ok(())
+}
+
+fn integrate(
+    repo: &Path,
+    file_type_manager: &FileTypeManager,
+    strategy: SyncStrategy,
+    upstream: &str,
+    autostash: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut args = match strategy {
+        SyncStrategy::Rebase => vec!["rebase"],
+        SyncStrategy::Merge => vec!["merge", "--no-edit"],
+    };
+    if autostash {
+        args.push("--autostash");
+    }
+    args.push(upstream);
+
+    let stash_before = stash_top(repo);
+    println!("{}", t!("sync.running", command = args.join(" ")).yellow());
+    let result = exec_git_command(repo, &args);
+
+    // rebase/merge 成功时，恢复自动暂存的修改仍可能冲突：git 会留下冲突标记并把修改保留在 stash 中
+    let conflicts = conflicted_files(repo);
+    let stash_kept = autostash && stash_top(repo).is_some_and(|top| Some(&top) != stash_before.as_ref());
+
+    match result {
+        Ok(()) if conflicts.is_empty() && !stash_kept => Ok(()),
+        Ok(()) => {
+            if !conflicts.is_empty() {
+                show_conflicts(repo, file_type_manager, None, &conflicts, false);
+            }
+            if stash_kept {
+                println!("{}", t!("sync.stash_kept").yellow());
+            }
+            Err(t!("sync.stash_conflict").into())
+        }
+        Err(e) if conflicts.is_empty() => Err(e),
+        Err(_) => {
+            show_conflicts(repo, file_type_manager, Some(strategy), &conflicts, autostash);
+            Err(t!("sync.conflict_stopped").into())
+        }
+    }
+}
+
+/// 最新 stash 的提交，用于判断自动暂存是否被保留
+fn stash_top(repo: &Path) -> Option<String> {
+    git_output(repo, &["rev-parse", "-q", "--verify", "refs/stash"])
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+fn conflicted_files(repo: &Path) -> Vec<String> {
+    git_output(repo, &["diff", "--name-only", "--diff-filter=U"])
+        .map(|out| out.lines().filter(|l| !l.is_empty()).map(String::from).collect())
+        .unwrap_or_default()
+}
+
+/// 冲突文件附带分类，便于判断该找谁确认；并给出继续或放弃的命令。
+/// `strategy` 为 None 表示 rebase/merge 已完成，冲突来自恢复自动暂存的修改
+fn show_conflicts(
+    repo: &Path,
+    file_type_manager: &FileTypeManager,
+    strategy: Option<SyncStrategy>,
+    files: &[String],
+    autostash: bool,
+) {
+    println!();
+    println!("{}", t!("sync.conflicts").red());
+    for file in files {
+        println!("  {} [{}]", file, file_type_manager.get_file_category(repo, file).cyan());
+    }
+
+    let (continue_command, abort_command) = match strategy {
+        Some(SyncStrategy::Rebase) => ("git rebase --continue", "git rebase --abort"),
+        Some(SyncStrategy::Merge) => ("git merge --continue", "git merge --abort"),
+        None => {
+            println!();
+            println!("{}", t!("sync.resolve_hint").yellow());
+            println!("  git add <file>...");
+            println!("  git stash drop");
+            println!("  cyber-zen sync");
+            return;
+        }
+    };
+
+    println!();
+    println!("{}", t!("sync.resolve_hint").yellow());
+    println!("  git add <file>...");
+    println!("  {}", continue_command);
+    println!("  cyber-zen sync");
+    println!("{}", t!("sync.abort_hint").yellow());
+    println!("  {}", abort_command);
+    if autostash {
+        println!("{}", t!("sync.autostash_hint").yellow());
+    }
+}
@@ -0,0 +1,309 @@
+use colored::*;
+use crate::commands::gcm::{analyze_git_changes, analyze_staged_changes, generate_message_from_changes, resolve_style};
+use crate::config::{load_settings, FileTypeManager, WipMode};
+use crate::git::{check_git_repo, current_branch, exec_git_command, git_output};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// stash 信息前缀，用于从 `git stash list` 中识别本工具创建的检查点
+const STASH_PREFIX: &str = "cyber-zen wip: ";
+
+/// ref 模式的检查点以提交链的形式保存在 `refs/wip/<分支>`，不出现在分支历史中
+const REF_PREFIX: &str = "refs/wip/";
+
+/// 检查点
+struct Checkpoint {
+    /// stash 模式为 `stash@{n}`，ref 模式为提交哈希
+    id: String,
+    age: String,
+    description: String,
+}
+
+fn resolve_mode(repo: &Path, mode: Option<WipMode>) -> Result<WipMode, Box<dyn std::error::Error>> {
+    Ok(match mode {
+        Some(mode) => mode,
+        None => load_settings(repo)?.wip.mode,
+    })
+}
+
+fn wip_ref(repo: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let branch = current_branch(repo);
+    if branch == "HEAD" {
+        return Err(t!("sync.detached").into());
+    }
+    Ok(format!("{}{}", REF_PREFIX, branch))
+}
+
+pub fn run_wip_save(message: Option<String>, mode: Option<WipMode>) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    if git_output(repo, &["status", "--porcelain"])?.trim().is_empty() {
+        return Err(t!("wip.nothing").into());
+    }
+
+    let description = match message {
+        Some(message) => message,
+        None => describe_changes(repo)?,
+    };
+
+    match resolve_mode(repo, mode)? {
+        WipMode::Stash => {
+            let stash_message = format!("{}{}", STASH_PREFIX, description);
+            git_output(repo, &["stash", "push", "--include-untracked", "-m", &stash_message])?;
+            println!("{} {}", t!("wip.stashed").green(), description);
+        }
+        WipMode::Ref => {
+            let wip_ref = wip_ref(repo)?;
+            match save_checkpoint(repo, &wip_ref, &description)? {
+                Some(commit) => println!("{} {} {}", t!("wip.saved").green(), short(&commit).yellow(), description),
+                None => println!("{}", t!("wip.unchanged").yellow()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+pub fn run_wip_list(mode: Option<WipMode>) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let checkpoints = list_checkpoints(repo, resolve_mode(repo, mode)?)?;
+    if checkpoints.is_empty() {
+        println!("{}", t!("wip.empty").yellow());
+        return Ok(());
+    }
+
+    println!("{}", t!("wip.list_title", branch = current_branch(repo)).cyan());
+    for (i, checkpoint) in checkpoints.iter().enumerate() {
+        println!(
+            "  {}. {} {} {}",
+            i + 1,
+            short(&checkpoint.id).yellow(),
+            checkpoint.description,
+            format!("({})", checkpoint.age).dimmed()
+        );
+    }
+
+    Ok(())
+}
+
+/// `index` 为 `wip list` 中的编号，默认恢复最新的检查点
+pub fn run_wip_restore(index: Option<usize>, mode: Option<WipMode>) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    let mode = resolve_mode(repo, mode)?;
+    let checkpoints = list_checkpoints(repo, mode)?;
+    let index = index.unwrap_or(1);
+    let checkpoint = checkpoints
+        .get(index.wrapping_sub(1))
+        .ok_or_else(|| if checkpoints.is_empty() { t!("wip.empty") } else { t!("common.number_out_of_range", value = index) })?;
+
+    match mode {
+        WipMode::Stash => exec_git_command(repo, &["stash", "pop", &checkpoint.id])?,
+        WipMode::Ref => {
+            // 当前内容不在任何检查点中时，覆盖工作区之前先保存，避免丢失
+            let wip_ref = wip_ref(repo)?;
+            let current = snapshot_tree(repo)?;
+            let mut saved = false;
+            for c in &checkpoints {
+                saved |= tree_of(repo, &c.id)? == current;
+            }
+            if !saved {
+                if let Some(commit) = save_checkpoint(repo, &wip_ref, &t!("wip.before_restore"))? {
+                    println!("{} {}", t!("wip.auto_saved").yellow(), short(&commit));
+                }
+            }
+
+            // git restore 只会删除已跟踪的文件，检查点之后新建的未跟踪文件需要手动删除
+            let added = git_output(repo, &["diff-tree", "-r", "--name-only", "--diff-filter=D", &current, &checkpoint.id])?;
+            let root = git_output(repo, &["rev-parse", "--show-toplevel"])?;
+            for file in added.lines().filter(|l| !l.is_empty()) {
+                let _ = fs::remove_file(Path::new(root.trim()).join(file));
+            }
+            git_output(repo, &["restore", &format!("--source={}", checkpoint.id), "--worktree", "--", ":/"])?;
+        }
+    }
+
+    println!("{} {}", t!("wip.restored").green(), checkpoint.description);
+    Ok(())
+}
+
+/// 将 ref 模式的检查点和当前修改合并为分支上的一个提交，提交信息按 gcm 的分析结果生成
+pub fn run_wip_squash(message: Option<String>, mode: Option<WipMode>) -> Result<(), Box<dyn std::error::Error>> {
+    let repo = Path::new(".");
+    check_git_repo(repo)?;
+
+    // stash 之间互相独立，没有可以合并的历史
+    if resolve_mode(repo, mode)? == WipMode::Stash {
+        return Err(t!("wip.squash_stash").into());
+    }
+
+    let wip_ref = wip_ref(repo)?;
+    if list_checkpoints(repo, WipMode::Ref)?.is_empty() {
+        return Err(t!("wip.empty").into());
+    }
+
+    // 最后一个检查点之后的修改也一并保存
+    save_checkpoint(repo, &wip_ref, &t!("wip.before_squash"))?;
+    let checkpoints = list_checkpoints(repo, WipMode::Ref)?;
+    let latest = &checkpoints[0];
+
+    // 暂存区和工作区都切换到最新检查点的内容
+    git_output(repo, &["read-tree", "--reset", "-u", &latest.id])?;
+
+    let message = match message {
+        Some(message) => message,
+        None => {
+            let file_type_manager = FileTypeManager::new()?;
+            let style = resolve_style(repo, &file_type_manager, None)?;
+            let changes = analyze_staged_changes(repo, &file_type_manager)?;
+            if changes.is_empty() {
+                return Err(t!("wip.nothing").into());
+            }
+            generate_message_from_changes(&changes, &file_type_manager, style)
+        }
+    };
+
+    println!("{} {}", t!("gcm.message").cyan(), message);
+    exec_git_command(repo, &["commit", "-m", &message, "--no-verify"])?;
+    git_output(repo, &["update-ref", "-d", &wip_ref])?;
+
+    println!("{}", t!("wip.squashed", count = checkpoints.len()).green());
+    Ok(())
+}
+
+/// 用 gcm 的分析结果生成描述，即提交信息的首行
+fn describe_changes(repo: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let file_type_manager = FileTypeManager::new()?;
+    let style = resolve_style(repo, &file_type_manager, None)?;
+    let changes = analyze_git_changes(repo, &file_type_manager)?;
+    if changes.is_empty() {
+        return Err(t!("wip.nothing").into());
+    }
+
+    let message = generate_message_from_changes(&changes, &file_type_manager, style);
+    Ok(message.lines().next().unwrap_or_default().to_string())
+}
+
+fn list_checkpoints(repo: &Path, mode: WipMode) -> Result<Vec<Checkpoint>, Box<dyn std::error::Error>> {
+    let mut checkpoints = Vec::new();
+
+    match mode {
+        WipMode::Stash => {
+            // 主题形如 "On main: cyber-zen wip: 描述"，只列出当前分支的检查点
+            let branch = current_branch(repo);
+            let list = git_output(repo, &["stash", "list", "--format=%gd%x1f%cr%x1f%gs"])?;
+            for line in list.lines() {
+                let fields: Vec<&str> = line.splitn(3, '\x1f').collect();
+                if fields.len() < 3 {
+                    continue;
+                }
+                let Some((on_branch, subject)) = fields[2].split_once(": ") else {
+                    continue;
+                };
+                let Some(description) = subject.strip_prefix(STASH_PREFIX) else {
+                    continue;
+                };
+                if on_branch.strip_prefix("On ") != Some(branch.as_str()) {
+                    continue;
+                }
+                checkpoints.push(Checkpoint {
+                    id: fields[0].to_string(),
+                    age: fields[1].to_string(),
+                    description: description.to_string(),
+                });
+            }
+        }
+        WipMode::Ref => {
+            let wip_ref = wip_ref(repo)?;
+            if git_output(repo, &["rev-parse", "--verify", "-q", &wip_ref]).is_err() {
+                return Ok(checkpoints);
+            }
+            let log = git_output(repo, &["log", "--format=%H%x1f%cr%x1f%s", &wip_ref, "--not", "HEAD"])?;
+            for line in log.lines() {
+                let fields: Vec<&str> = line.splitn(3, '\x1f').collect();
+                if fields.len() == 3 {
+                    checkpoints.push(Checkpoint {
+                        id: fields[0].to_string(),
+                        age: fields[1].to_string(),
+                        description: fields[2].to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(checkpoints)
+}
+
+/// 在不改动暂存区和工作区的前提下保存检查点；与上一个检查点相同时返回 None
+fn save_checkpoint(repo: &Path, wip_ref: &str, description: &str) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    let tree = snapshot_tree(repo)?;
+
+    // 检查点链的基础是创建时的 HEAD；分支已前进到链之外时重新从 HEAD 开始
+    let previous = git_output(repo, &["rev-parse", "--verify", "-q", wip_ref])
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|commit| git_output(repo, &["merge-base", "--is-ancestor", "HEAD", commit]).is_ok());
+    let parent = match previous {
+        Some(commit) => {
+            if tree_of(repo, &commit)? == tree {
+                return Ok(None);
+            }
+            commit
+        }
+        None => git_output(repo, &["rev-parse", "HEAD"])?.trim().to_string(),
+    };
+
+    let commit = git_output(repo, &["commit-tree", &tree, "-p", &parent, "-m", description])?
+        .trim()
+        .to_string();
+    git_output(repo, &["update-ref", "-m", &format!("wip: {}", description), wip_ref, &commit])?;
+    Ok(Some(commit))
+}
+
+/// 用临时暂存区记录工作区（含未跟踪文件）的树对象
+fn snapshot_tree(repo: &Path) -> Result<String, Box<dyn std::error::Error>> {
+    let index = git_output(repo, &["rev-parse", "--git-path", "cyber-zen-wip-index"])?;
+    let index = repo.join(index.trim());
+
+    let result = (|| {
+        git_with_index(repo, &index, &["read-tree", "HEAD"])?;
+        git_with_index(repo, &index, &["add", "-A", "--", ":/"])?;
+        git_with_index(repo, &index, &["write-tree"])
+    })();
+
+    let _ = fs::remove_file(&index);
+    Ok(result?.trim().to_string())
+}
+
+fn git_with_index(repo: &Path, index: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(repo)
+        .env("GIT_INDEX_FILE", index)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(t!("git.output_failed", command = args.first().unwrap_or(&""), error = stderr.trim()).into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn tree_of(repo: &Path, commit: &str) -> Result<String, Box<dyn std::error::Error>> {
+    Ok(git_output(repo, &["rev-parse", &format!("{}^{{tree}}", commit)])?.trim().to_string())
+}
+
+fn short(id: &str) -> &str {
+    if id.starts_with("stash@") {
+        id
+    } else {
+        id.get(..7).unwrap_or(id)
+    }
+}
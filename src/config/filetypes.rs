@@ -1,30 +1,57 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
-use crate::config::get_config_dir;
+use crate::config::{builtin_styles, get_config_dir, CommitStyle};
+use crate::config::rules::{normalize_path, CategoryMatch, CategoryRule, FileTypeInfo};
 use std::fs;
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FileTypeItem {
+    /// 文件名后缀，多个后缀同时命中时最长者胜出（如 ".d.ts" 优先于 ".ts"）
+    #[serde(default)]
     pub extensions: Vec<String>,
+    /// 完整文件名，如 "Dockerfile"、"Cargo.toml"，优先于后缀匹配
+    #[serde(default)]
+    pub filenames: Vec<String>,
+    /// 无扩展名文件按 shebang 中的解释器识别，如 "bash"、"python"
+    #[serde(default)]
+    pub interpreters: Vec<String>,
     pub description: String,
+    /// 显式优先级，高于匹配精确度；相同时按精确度和声明顺序
+    #[serde(default)]
+    pub priority: i32,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FileTypeConfig {
-    pub file_types: HashMap<String, HashMap<String, FileTypeItem>>,
+    pub file_types: IndexMap<String, IndexMap<String, FileTypeItem>>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CategoryPattern {
+    /// 完整匹配某一级目录名或文件名（不含扩展名）
+    #[serde(default)]
     pub patterns: Vec<String>,
+    /// glob 模式，如 "**/migrations/**"
+    #[serde(default)]
+    pub globs: Vec<String>,
+    /// 针对完整相对路径的正则
+    #[serde(default)]
+    pub regexes: Vec<String>,
+    /// 文件类型键名（如 "react"、"frontend.react"）或描述
+    #[serde(default)]
+    pub file_types: Vec<String>,
     pub description: String,
+    /// 多条规则同时命中时，优先级高者胜出；相同优先级按配置中的声明顺序
+    #[serde(default)]
+    pub priority: i32,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CategoryConfigInner {
     #[serde(rename = "directory_patterns")]
-    pub directory_patterns: HashMap<String, CategoryPattern>,
+    pub directory_patterns: IndexMap<String, CategoryPattern>,
     pub default: String,
 }
 
@@ -35,7 +62,7 @@ pub struct CategoryConfigWrapper {
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CategoryConfig {
-    pub directory_patterns: HashMap<String, CategoryPattern>,
+    pub directory_patterns: IndexMap<String, CategoryPattern>,
     pub default: String,
 }
 
@@ -44,6 +71,9 @@ pub struct CommitTemplateConfig {
     pub prefixes: HashMap<String, String>,
     pub descriptions: HashMap<String, String>,
     pub actions: HashMap<String, String>,
+    /// 自定义或覆盖内置的提交风格预设
+    #[serde(default)]
+    pub styles: IndexMap<String, CommitStyle>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -55,7 +85,9 @@ pub struct CommitTemplateConfigWrapper {
 pub struct FileTypeManager {
     file_types: FileTypeConfig,
     categories: CategoryConfig,
+    category_rules: Vec<CategoryRule>,
     commit_templates: CommitTemplateConfig,
+    commit_styles: IndexMap<String, CommitStyle>,
 }
 
 impl FileTypeManager {
@@ -64,37 +96,124 @@ impl FileTypeManager {
         
         let file_types = load_file_type_config(&config_dir)?;
         let categories = load_category_config(&config_dir)?;
-        let commit_templates = load_commit_template_config(&config_dir)?;
-        
+        let mut commit_templates = load_commit_template_config(&config_dir)?;
+
+        let mut commit_styles = builtin_styles();
+        commit_styles.extend(std::mem::take(&mut commit_templates.styles));
+
+        let mut category_rules = categories
+            .directory_patterns
+            .iter()
+            .map(|(name, pattern)| CategoryRule::compile(name, pattern))
+            .collect::<Result<Vec<_>, _>>()?;
+        // 稳定排序：同优先级保持声明顺序
+        category_rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+
         Ok(FileTypeManager {
             file_types,
             categories,
+            category_rules,
             commit_templates,
+            commit_styles,
         })
     }
     
-    pub fn get_file_type(&self, filename: &str) -> String {
-        for category in self.file_types.file_types.values() {
-            for type_item in category.values() {
-                for ext in &type_item.extensions {
-                    if filename.ends_with(ext) {
-                        return type_item.description.clone();
-                    }
+    /// `root` 为 `filepath` 所在仓库的根目录，仅在需要读取 shebang 时使用
+    pub fn get_file_type(&self, root: &Path, filepath: &str) -> String {
+        self.find_file_type(root, &normalize_path(filepath))
+            .map(|info| info.description.to_string())
+            .unwrap_or_else(|| t!("config.other_file_type"))
+    }
+
+    /// 依次尝试完整文件名、后缀和 shebang；多项命中时按 (priority, 精确度, 声明顺序) 取最优
+    fn find_file_type(&self, root: &Path, path: &str) -> Option<FileTypeInfo<'_>> {
+        let file_name = path.rsplit('/').next().unwrap_or(path);
+        let lower_name = file_name.to_lowercase();
+
+        // 精确度：完整文件名最高，其次按后缀长度
+        let mut best: Option<((i32, usize), FileTypeInfo)> = None;
+
+        for (group, category) in &self.file_types.file_types {
+            for (key, type_item) in category {
+                let specificity = if type_item.filenames.iter().any(|f| f.to_lowercase() == lower_name) {
+                    Some(usize::MAX)
+                } else {
+                    type_item
+                        .extensions
+                        .iter()
+                        .filter(|ext| lower_name.ends_with(&ext.to_lowercase()))
+                        .map(|ext| ext.len())
+                        .max()
+                };
+
+                let Some(specificity) = specificity else {
+                    continue;
+                };
+                let rank = (type_item.priority, specificity);
+                if best.as_ref().is_none_or(|(r, _)| rank > *r) {
+                    best = Some((
+                        rank,
+                        FileTypeInfo {
+                            group,
+                            key,
+                            description: &type_item.description,
+                        },
+                    ));
                 }
             }
         }
-        "其他文件".to_string()
-    }
-    
-    pub fn get_file_category(&self, filepath: &str) -> String {
-        for pattern in self.categories.directory_patterns.values() {
-            for pat in &pattern.patterns {
-                if filepath.contains(pat) {
-                    return pattern.description.clone();
+
+        if let Some((_, info)) = best {
+            return Some(info);
+        }
+
+        // 没有扩展名的脚本按 shebang 识别
+        if file_name.trim_start_matches('.').contains('.') {
+            return None;
+        }
+        let interpreter = read_shebang_interpreter(&root.join(path))?;
+
+        let mut best: Option<(i32, FileTypeInfo)> = None;
+        for (group, category) in &self.file_types.file_types {
+            for (key, type_item) in category {
+                let matched = type_item.interpreters.iter().any(|name| interpreter_matches(&interpreter, name));
+                if matched && best.as_ref().is_none_or(|(p, _)| type_item.priority > *p) {
+                    best = Some((
+                        type_item.priority,
+                        FileTypeInfo {
+                            group,
+                            key,
+                            description: &type_item.description,
+                        },
+                    ));
                 }
             }
         }
-        self.categories.default.clone()
+
+        best.map(|(_, info)| info)
+    }
+    
+    pub fn get_file_category(&self, root: &Path, filepath: &str) -> String {
+        self.explain_file_category(root, filepath)
+            .into_iter()
+            .next()
+            .map(|m| m.description)
+            .unwrap_or_else(|| self.categories.default.clone())
+    }
+
+    /// 返回所有命中的分类规则，按优先级从高到低排列（同优先级按声明顺序），第一条即最终分类
+    pub fn explain_file_category(&self, root: &Path, filepath: &str) -> Vec<CategoryMatch> {
+        let path = normalize_path(filepath);
+        let file_type = self.find_file_type(root, &path);
+
+        self.category_rules
+            .iter()
+            .filter_map(|rule| rule.find_match(&path, file_type.as_ref()))
+            .collect()
+    }
+
+    pub fn get_default_category(&self) -> &str {
+        &self.categories.default
     }
     
     pub fn get_commit_type(&self, added: i32, modified: i32, deleted: i32) -> String {
@@ -111,14 +230,32 @@ impl FileTypeManager {
         }
     }
     
-    #[allow(dead_code)]
+    pub fn is_commit_type(&self, commit_type: &str) -> bool {
+        self.commit_templates.prefixes.contains_key(commit_type)
+    }
+
     pub fn get_commit_description(&self, commit_type: &str) -> String {
         self.commit_templates.descriptions
             .get(commit_type)
             .cloned()
-            .unwrap_or_else(|| "更新项目".to_string())
+            .unwrap_or_else(|| tc!("config.default_description"))
     }
     
+    pub fn commit_style(&self, name: &str) -> Result<&CommitStyle, Box<dyn std::error::Error>> {
+        self.commit_styles.get(name).ok_or_else(|| {
+            let names: Vec<&str> = self.commit_styles.keys().map(|k| k.as_str()).collect();
+            t!("config.unknown_style", name = name, available = names.join(", ")).into()
+        })
+    }
+
+    /// 分类规则名（如 "components"），未命中任何规则时为空
+    pub fn get_file_category_name(&self, root: &Path, filepath: &str) -> Option<String> {
+        self.explain_file_category(root, filepath)
+            .into_iter()
+            .next()
+            .map(|m| m.rule)
+    }
+
     pub fn get_action_description(&self, action: &str) -> String {
         self.commit_templates.actions
             .get(action)
@@ -127,13 +264,38 @@ impl FileTypeManager {
     }
 }
 
+/// 读取首行 `#!`，返回解释器名：`#!/usr/bin/env python3` 和 `#!/usr/bin/python3` 都得到 "python3"
+fn read_shebang_interpreter(path: &Path) -> Option<String> {
+    use std::io::Read;
+
+    let mut buf = [0u8; 256];
+    let n = fs::File::open(path).ok()?.read(&mut buf).ok()?;
+    let head = String::from_utf8_lossy(&buf[..n]);
+    let first_line = head.lines().next()?.strip_prefix("#!")?;
+
+    let mut parts = first_line.split_whitespace();
+    let mut program = parts.next()?.rsplit('/').next()?;
+    if program == "env" {
+        program = parts.find(|p| !p.starts_with('-'))?;
+    }
+
+    Some(program.to_string())
+}
+
+/// "python3"、"python3.11" 均匹配配置中的 "python"
+fn interpreter_matches(interpreter: &str, name: &str) -> bool {
+    interpreter
+        .strip_prefix(name)
+        .is_some_and(|rest| rest.chars().all(|c| c.is_ascii_digit() || c == '.'))
+}
+
 fn load_file_type_config(config_dir: &Path) -> Result<FileTypeConfig, Box<dyn std::error::Error>> {
     let config_path = config_dir.join("file-types.toml");
 
     if !config_path.exists() {
         // 返回默认配置
         return Ok(FileTypeConfig {
-            file_types: HashMap::new(),
+            file_types: IndexMap::new(),
         });
     }
 
@@ -148,7 +310,7 @@ fn load_category_config(config_dir: &Path) -> Result<CategoryConfig, Box<dyn std
     if !config_path.exists() {
         // 返回默认配置
         return Ok(CategoryConfig {
-            directory_patterns: HashMap::new(),
+            directory_patterns: IndexMap::new(),
             default: "项目文件".to_string(),
         });
     }
@@ -196,6 +358,7 @@ fn load_commit_template_config(config_dir: &Path) -> Result<CommitTemplateConfig
             prefixes,
             descriptions,
             actions,
+            styles: IndexMap::new(),
         });
     }
 
@@ -1,6 +1,12 @@
+#[allow(clippy::module_inception)]
 mod config;
 mod filetypes;
+mod rules;
+mod settings;
+mod styles;
 
 pub use config::*;
 pub use filetypes::*;
+pub use settings::*;
+pub use styles::*;
 
@@ -0,0 +1,126 @@
+use globset::{GlobBuilder, GlobMatcher};
+use regex::Regex;
+use serde::Serialize;
+use crate::config::CategoryPattern;
+
+/// 编译后的分类规则
+pub struct CategoryRule {
+    pub name: String,
+    pub description: String,
+    pub priority: i32,
+    matchers: Vec<PathMatcher>,
+}
+
+enum PathMatcher {
+    /// 与某一级路径（目录名或去掉扩展名的文件名）完全相同，忽略大小写
+    Segment(String),
+    Glob(String, GlobMatcher),
+    Regex(Regex),
+    /// 匹配文件类型的键名（如 "react"、"frontend.react"）或描述
+    FileType(String),
+}
+
+/// 命中的分类规则，用于解释分类结果
+#[derive(Debug, Clone, Serialize)]
+pub struct CategoryMatch {
+    pub rule: String,
+    pub kind: &'static str,
+    pub pattern: String,
+    pub priority: i32,
+    pub description: String,
+}
+
+/// 文件类型的识别结果，供 file_types 规则匹配
+pub struct FileTypeInfo<'a> {
+    pub group: &'a str,
+    pub key: &'a str,
+    pub description: &'a str,
+}
+
+impl CategoryRule {
+    pub fn compile(name: &str, pattern: &CategoryPattern) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut matchers = Vec::new();
+
+        for segment in &pattern.patterns {
+            matchers.push(PathMatcher::Segment(segment.to_lowercase()));
+        }
+
+        for glob in &pattern.globs {
+            let matcher = GlobBuilder::new(glob)
+                .case_insensitive(true)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| t!("config.invalid_glob", rule = name, glob = glob, error = e))?
+                .compile_matcher();
+            matchers.push(PathMatcher::Glob(glob.clone(), matcher));
+        }
+
+        for regex in &pattern.regexes {
+            let re = Regex::new(regex)
+                .map_err(|e| t!("config.invalid_regex", rule = name, regex = regex, error = e))?;
+            matchers.push(PathMatcher::Regex(re));
+        }
+
+        for file_type in &pattern.file_types {
+            matchers.push(PathMatcher::FileType(file_type.clone()));
+        }
+
+        Ok(CategoryRule {
+            name: name.to_string(),
+            description: pattern.description.clone(),
+            priority: pattern.priority,
+            matchers,
+        })
+    }
+
+    /// 返回第一个命中的匹配器
+    pub fn find_match(&self, path: &str, file_type: Option<&FileTypeInfo>) -> Option<CategoryMatch> {
+        let segments = path_segments(path);
+
+        self.matchers.iter().find_map(|matcher| {
+            let (kind, pattern) = match matcher {
+                PathMatcher::Segment(seg) if segments.iter().any(|s| s == seg) => ("segment", seg.clone()),
+                PathMatcher::Glob(glob, m) if m.is_match(path) => ("glob", glob.clone()),
+                PathMatcher::Regex(re) if re.is_match(path) => ("regex", re.as_str().to_string()),
+                PathMatcher::FileType(name) if file_type.is_some_and(|t| file_type_matches(t, name)) => {
+                    ("file_type", name.clone())
+                }
+                _ => return None,
+            };
+
+            Some(CategoryMatch {
+                rule: self.name.clone(),
+                kind,
+                pattern,
+                priority: self.priority,
+                description: self.description.clone(),
+            })
+        })
+    }
+}
+
+/// 统一使用 `/` 分隔并去掉开头的 `./`
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.trim_start_matches("./").to_string()
+}
+
+/// 所有目录名加上文件名及其去掉扩展名后的名字，统一小写
+fn path_segments(path: &str) -> Vec<String> {
+    let parts: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let mut segments: Vec<String> = parts.iter().map(|s| s.to_lowercase()).collect();
+
+    if let Some(file_name) = parts.last() {
+        if let Some((stem, _)) = file_name.split_once('.') {
+            if !stem.is_empty() {
+                segments.push(stem.to_lowercase());
+            }
+        }
+    }
+
+    segments
+}
+
+fn file_type_matches(info: &FileTypeInfo, name: &str) -> bool {
+    name == info.key || name == info.description || name == format!("{}.{}", info.group, info.key)
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::config::get_config_dir;
+
+/// 通用行为配置（settings.toml），与文件类型等规则配置分开
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub gcm: GcmSettings,
+    pub sync: SyncSettings,
+    pub wip: WipSettings,
+    pub i18n: I18nSettings,
+    pub compress: CompressSettings,
+}
+
+/// 缩放时使用的重采样滤镜
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum ResampleFilter {
+    /// 最清晰，适合照片
+    #[default]
+    Lanczos3,
+    CatmullRom,
+    /// 双线性，速度快
+    Triangle,
+    /// 不插值，适合像素画
+    Nearest,
+}
+
+/// 缩小后的 USM 锐化参数
+#[derive(Debug, Clone, Copy, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SharpenSettings {
+    /// 高斯模糊半径
+    pub sigma: f32,
+    /// 差值低于该值的像素不锐化，避免放大噪点
+    pub threshold: i32,
+    /// 缩放比例不大于该值（即缩小较多）时才锐化
+    pub below_scale: f64,
+}
+
+impl Default for SharpenSettings {
+    fn default() -> Self {
+        SharpenSettings { sigma: 0.5, threshold: 1, below_scale: 0.5 }
+    }
+}
+
+/// compress 的 --preset，未设置的项使用命令行参数或默认值
+#[derive(Debug, Default, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CompressPreset {
+    pub filter: Option<ResampleFilter>,
+    /// 在线性光空间缩小，默认开启
+    pub linear: Option<bool>,
+    pub sharpen: Option<SharpenSettings>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct CompressSettings {
+    pub presets: BTreeMap<String, CompressPreset>,
+}
+
+/// 检查点的保存方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum WipMode {
+    /// 保存为 git stash，工作区随之清空
+    #[default]
+    Stash,
+    /// 以提交链保存在 refs/wip/<分支>，工作区保持不变
+    Ref,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct WipSettings {
+    /// stash 或 ref，可用 --mode 临时覆盖
+    pub mode: WipMode,
+}
+
+/// 拉取上游变更的方式
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStrategy {
+    #[default]
+    Rebase,
+    Merge,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SyncSettings {
+    /// rebase 或 merge，可用 --rebase/--merge 临时覆盖
+    pub strategy: SyncStrategy,
+    /// 同步前自动暂存未提交的修改，完成后恢复
+    pub autostash: bool,
+    /// 同步后推送本地提交
+    pub push: bool,
+}
+
+impl Default for SyncSettings {
+    fn default() -> Self {
+        SyncSettings { strategy: SyncStrategy::Rebase, autostash: true, push: true }
+    }
+}
+
+/// 界面语言与提交信息语言分开设置，例如界面使用中文、提交历史使用英文
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct I18nSettings {
+    /// 界面语言：zh-CN 或 en
+    pub lang: Option<String>,
+    /// 提交信息和变更日志的语言，默认同界面语言
+    pub commit_lang: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(default)]
+pub struct GcmSettings {
+    /// 默认签名提交（GPG/SSH 由 git 的 gpg.format 决定）
+    pub sign: bool,
+    /// 指定签名密钥，等同于 `git commit -S<key>`
+    pub signing_key: Option<String>,
+    /// 默认追加 Signed-off-by
+    pub signoff: bool,
+    /// 常用结对伙伴，格式为 "Name <email>"
+    pub co_authors: Vec<String>,
+    /// monorepo 中拒绝跨多个包的提交，可用 --allow-cross-package 临时放行
+    pub strict_packages: bool,
+    /// gitignore 语法的忽略规则，与仓库的 .cyber-zenignore 合并
+    pub ignore: Vec<String>,
+    /// 提交风格预设：chinese、conventional、gitmoji、angular、sentence 或自定义
+    pub style: Option<String>,
+}
+
+/// 仓库根目录下的本地配置，结构与 settings.toml 相同，同名项覆盖全局配置
+pub const LOCAL_SETTINGS_FILE: &str = ".cyber-zen.toml";
+
+pub fn load_settings(repo: &Path) -> Result<Settings, Box<dyn std::error::Error>> {
+    load_settings_from(&get_config_dir(), repo)
+}
+
+fn load_settings_from(config_dir: &Path, repo: &Path) -> Result<Settings, Box<dyn std::error::Error>> {
+    let mut merged = read_table(&config_dir.join("settings.toml"))?;
+
+    let local = read_table(&repo.join(LOCAL_SETTINGS_FILE))
+        .map_err(|e| t!("common.parse_failed", file = LOCAL_SETTINGS_FILE, error = e))?;
+    merge_tables(&mut merged, local);
+
+    let settings: Settings = toml::Value::Table(merged).try_into()?;
+    Ok(settings)
+}
+
+fn read_table(path: &Path) -> Result<toml::Table, Box<dyn std::error::Error>> {
+    if !path.exists() {
+        return Ok(toml::Table::new());
+    }
+
+    let content = fs::read_to_string(path)?;
+    Ok(toml::from_str(&content)?)
+}
+
+/// 逐层合并表，其他类型的值（包括数组）整体覆盖
+fn merge_tables(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge_tables(base_table, overlay_table);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 默认提交风格，即原有的中文 Conventional Commits
+pub const DEFAULT_STYLE: &str = "chinese";
+
+/// 未配置 `gcm.style` 时按提交信息语言选择：英文使用 conventional，其余使用默认风格
+pub fn default_style_name() -> &'static str {
+    match crate::i18n::commit_lang() {
+        crate::i18n::Lang::En => "conventional",
+        crate::i18n::Lang::ZhCn => DEFAULT_STYLE,
+    }
+}
+
+/// 提交信息风格预设：标题格式、摘要措辞和正文格式
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CommitStyle {
+    pub description: String,
+    /// 标题模板，占位符：{emoji} {type} {scope}（含括号）{summary}
+    pub header: String,
+    /// 变更类型改写，如 Angular 没有 cleanup，改用 refactor
+    #[serde(default)]
+    pub types: HashMap<String, String>,
+    /// 变更类型对应的 emoji，用于 {emoji}
+    #[serde(default)]
+    pub emojis: HashMap<String, String>,
+    /// 动作词（键同 commit_templates.actions），未配置的回退到 commit_templates.actions
+    #[serde(default)]
+    pub actions: HashMap<String, String>,
+    /// 单个文件变更的摘要，占位符：{action} {category}
+    pub single: String,
+    /// 多个分类的摘要，占位符：{action}（即 updated）{categories}
+    pub multiple: String,
+    /// 无法归纳时的摘要
+    pub fallback: String,
+    /// 多个分类之间的分隔符
+    pub separator: String,
+    /// 正文每行的格式，占位符：{action} {file}
+    pub detail: String,
+    /// 摘要首字母大写
+    #[serde(default)]
+    pub capitalize: bool,
+    /// 摘要中使用分类规则名（如 "components"）而不是中文描述
+    #[serde(default)]
+    pub category_names: bool,
+}
+
+impl CommitStyle {
+    /// 标题模板中包含 {type} 时，提交信息需以变更类型开头
+    pub fn has_type(&self) -> bool {
+        self.header.contains("{type}")
+    }
+
+    pub fn commit_type(&self, commit_type: &str) -> String {
+        self.types.get(commit_type).cloned().unwrap_or_else(|| commit_type.to_string())
+    }
+
+    pub fn render_header(&self, commit_type: &str, scope: Option<&str>, summary: &str) -> String {
+        let emoji = self.emojis.get(commit_type).map(|s| s.as_str()).unwrap_or("");
+        let scope = scope.map(|s| format!("({})", s)).unwrap_or_default();
+        let summary = if self.capitalize { capitalize(summary) } else { summary.to_string() };
+
+        self.header
+            .replace("{emoji}", emoji)
+            .replace("{type}", &self.commit_type(commit_type))
+            .replace("{scope}", &scope)
+            .replace("{summary}", &summary)
+            .trim()
+            .to_string()
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+fn map(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+    pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+}
+
+fn english_actions() -> HashMap<String, String> {
+    map(&[
+        ("added", "add"),
+        ("modified", "update"),
+        ("deleted", "remove"),
+        ("renamed", "rename"),
+        ("updated", "update"),
+    ])
+}
+
+/// 内置预设；commit-templates.toml 中同名的 `[commit_templates.styles.<name>]` 会覆盖
+pub fn builtin_styles() -> IndexMap<String, CommitStyle> {
+    let english = CommitStyle {
+        description: "Conventional Commits（英文）".to_string(),
+        header: "{type}{scope}: {summary}".to_string(),
+        types: map(&[("cleanup", "chore")]),
+        emojis: HashMap::new(),
+        actions: english_actions(),
+        single: "{action} {category}".to_string(),
+        multiple: "{action} {categories}".to_string(),
+        fallback: "update project files".to_string(),
+        separator: ", ".to_string(),
+        detail: "- {action} {file}".to_string(),
+        capitalize: false,
+        category_names: true,
+    };
+
+    let mut styles = IndexMap::new();
+
+    styles.insert("conventional".to_string(), english.clone());
+
+    styles.insert(
+        "chinese".to_string(),
+        CommitStyle {
+            description: "Conventional Commits（中文摘要）".to_string(),
+            header: "{type}{scope}: {summary}".to_string(),
+            types: HashMap::new(),
+            actions: HashMap::new(),
+            single: "{action}{category}".to_string(),
+            multiple: "{action}{categories}".to_string(),
+            fallback: "更新项目文件".to_string(),
+            separator: "、".to_string(),
+            category_names: false,
+            ..english.clone()
+        },
+    );
+
+    styles.insert(
+        "gitmoji".to_string(),
+        CommitStyle {
+            description: "gitmoji + Conventional Commits".to_string(),
+            header: "{emoji} {type}{scope}: {summary}".to_string(),
+            emojis: map(&[
+                ("feat", "✨"),
+                ("fix", "🐛"),
+                ("docs", "📝"),
+                ("style", "💄"),
+                ("refactor", "♻️"),
+                ("perf", "⚡️"),
+                ("test", "✅"),
+                ("build", "📦"),
+                ("ci", "👷"),
+                ("chore", "🔧"),
+                ("cleanup", "🔥"),
+                ("revert", "⏪"),
+                ("breaking", "💥"),
+            ]),
+            ..english.clone()
+        },
+    );
+
+    styles.insert(
+        "angular".to_string(),
+        CommitStyle {
+            description: "Angular 提交规范".to_string(),
+            // Angular 只允许 build/ci/docs/feat/fix/perf/refactor/test 以及 revert
+            types: map(&[("cleanup", "refactor"), ("chore", "build"), ("style", "refactor")]),
+            detail: "* {action} {file}".to_string(),
+            ..english.clone()
+        },
+    );
+
+    styles.insert(
+        "sentence".to_string(),
+        CommitStyle {
+            description: "英文句子，不带类型前缀".to_string(),
+            header: "{summary}".to_string(),
+            capitalize: true,
+            ..english
+        },
+    );
+
+    styles
+}
@@ -0,0 +1,78 @@
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+pub fn check_git_repo(dir: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .arg("rev-parse")
+        .arg("--git-dir")
+        .current_dir(dir)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(t!("git.not_a_repo").into());
+    }
+
+    Ok(())
+}
+
+/// 在指定目录执行 git 命令，输出直接打印到终端
+pub fn exec_git_command(dir: &Path, args: &[&str]) -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::new("git");
+    cmd.args(args);
+    cmd.current_dir(dir);
+    cmd.stdout(Stdio::inherit());
+    cmd.stderr(Stdio::inherit());
+    cmd.stdin(Stdio::inherit());
+
+    let status = cmd.status()?;
+    if !status.success() {
+        return Err(t!("git.command_failed", command = args.join(" ")).into());
+    }
+
+    Ok(())
+}
+
+/// 在指定目录执行 git 命令并返回标准输出
+pub fn git_output(dir: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(t!("git.output_failed", command = args.first().unwrap_or(&""), error = stderr.trim()).into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 与 `git_output` 相同，并把 `input` 写入 git 的标准输入
+pub fn git_output_with_input(dir: &Path, args: &[&str], input: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+    let mut child = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(input)?;
+    }
+    let output = child.wait_with_output()?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(t!("git.output_failed", command = args.first().unwrap_or(&""), error = stderr.trim()).into());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub fn current_branch(dir: &Path) -> String {
+    git_output(dir, &["rev-parse", "--abbrev-ref", "HEAD"])
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| t!("git.no_branch"))
+}
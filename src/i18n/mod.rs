@@ -0,0 +1,185 @@
+use clap::Command;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::config::load_settings;
+
+/// 文本按 `模块.键` 存放，编译时嵌入二进制
+const ZH_CN_CATALOG: &str = include_str!("locales/zh-CN.toml");
+const EN_CATALOG: &str = include_str!("locales/en.toml");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Lang {
+    ZhCn,
+    En,
+}
+
+impl Lang {
+    /// 接受 "zh"、"zh-CN"、"zh_CN.UTF-8"、"en"、"en_US.UTF-8" 等写法；C/POSIX 不代表语言偏好，返回 None
+    pub fn parse(value: &str) -> Option<Lang> {
+        let value = value.trim().to_lowercase();
+        if value.starts_with("zh") {
+            Some(Lang::ZhCn)
+        } else if value.starts_with("en") {
+            Some(Lang::En)
+        } else {
+            None
+        }
+    }
+}
+
+struct Languages {
+    ui: Lang,
+    commit: Lang,
+}
+
+static LANGUAGES: OnceLock<Languages> = OnceLock::new();
+static CATALOGS: OnceLock<HashMap<Lang, HashMap<String, String>>> = OnceLock::new();
+
+/// 界面语言：`--lang` > 配置 `i18n.lang` > `LC_ALL`/`LC_MESSAGES`/`LANG` > 中文；
+/// 提交信息语言：配置 `i18n.commit_lang` > 界面语言
+pub fn init(cli_lang: Option<&str>) {
+    let settings = load_settings(std::path::Path::new(".")).unwrap_or_default();
+
+    let env_lang = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|value| !value.is_empty())
+        .and_then(|value| Lang::parse(&value));
+
+    let ui = cli_lang
+        .and_then(Lang::parse)
+        .or_else(|| settings.i18n.lang.as_deref().and_then(Lang::parse))
+        .or(env_lang)
+        .unwrap_or(Lang::ZhCn);
+    let commit = settings
+        .i18n
+        .commit_lang
+        .as_deref()
+        .and_then(Lang::parse)
+        .unwrap_or(ui);
+
+    let _ = LANGUAGES.set(Languages { ui, commit });
+}
+
+pub fn ui_lang() -> Lang {
+    LANGUAGES.get().map(|l| l.ui).unwrap_or(Lang::ZhCn)
+}
+
+pub fn commit_lang() -> Lang {
+    LANGUAGES.get().map(|l| l.commit).unwrap_or(Lang::ZhCn)
+}
+
+fn catalogs() -> &'static HashMap<Lang, HashMap<String, String>> {
+    CATALOGS.get_or_init(|| {
+        let mut catalogs = HashMap::new();
+        catalogs.insert(Lang::ZhCn, parse_catalog(ZH_CN_CATALOG));
+        catalogs.insert(Lang::En, parse_catalog(EN_CATALOG));
+        catalogs
+    })
+}
+
+/// 将嵌套的表展开为 "gcm.analyzing" 形式的键
+fn parse_catalog(content: &str) -> HashMap<String, String> {
+    fn flatten(prefix: &str, table: &toml::Table, out: &mut HashMap<String, String>) {
+        for (key, value) in table {
+            let key = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+            match value {
+                toml::Value::Table(inner) => flatten(&key, inner, out),
+                toml::Value::String(text) => {
+                    out.insert(key, text.clone());
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut out = HashMap::new();
+    if let Ok(table) = toml::from_str::<toml::Table>(content) {
+        flatten("", &table, &mut out);
+    }
+    out
+}
+
+/// 查找指定语言的文本，缺失时回退到中文，仍缺失则返回键名
+pub fn lookup(lang: Lang, key: &str) -> &str {
+    let catalogs = catalogs();
+    catalogs
+        .get(&lang)
+        .and_then(|c| c.get(key))
+        .or_else(|| catalogs.get(&Lang::ZhCn).and_then(|c| c.get(key)))
+        .map(|s| s.as_str())
+        .unwrap_or(key)
+}
+
+/// 替换模板中的 `{name}` 占位符
+pub fn fill(template: &str, args: &[(&str, String)]) -> String {
+    let mut text = template.to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{{}}}", name), value);
+    }
+    text
+}
+
+/// 界面文本：`t!("gcm.total", count = 3)`
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::lookup($crate::i18n::ui_lang(), $key).to_string()
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::lookup($crate::i18n::ui_lang(), $key),
+            &[$((stringify!($name), $value.to_string())),+],
+        )
+    };
+}
+
+/// 写入提交历史的文本（提交信息、变更日志），使用提交信息语言
+#[macro_export]
+macro_rules! tc {
+    ($key:expr) => {
+        $crate::i18n::lookup($crate::i18n::commit_lang(), $key).to_string()
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::fill(
+            $crate::i18n::lookup($crate::i18n::commit_lang(), $key),
+            &[$((stringify!($name), $value.to_string())),+],
+        )
+    };
+}
+
+/// 命令行帮助默认来自源码中的中文文档注释，其他语言按 `cli.<子命令路径>.<参数名>` 替换
+pub fn localize_command(command: Command) -> Command {
+    if ui_lang() == Lang::ZhCn {
+        return command;
+    }
+    localize_with_prefix(command, "cli")
+}
+
+fn localize_with_prefix(mut command: Command, prefix: &str) -> Command {
+    let lang = ui_lang();
+    let translated = |key: String| {
+        let text = lookup(lang, &key);
+        (text != key).then(|| text.to_string())
+    };
+
+    if let Some(about) = translated(format!("{}.about", prefix)) {
+        command = command.about(about);
+    }
+
+    let arg_ids: Vec<String> = command.get_arguments().map(|a| a.get_id().to_string()).collect();
+    for id in arg_ids {
+        if let Some(help) = translated(format!("{}.{}", prefix, id)) {
+            command = command.mut_arg(id.as_str(), |arg| arg.help(help));
+        }
+    }
+
+    let subcommands: Vec<String> = command.get_subcommands().map(|s| s.get_name().to_string()).collect();
+    for name in subcommands {
+        let sub_prefix = format!("{}.{}", prefix, name);
+        command = command.mut_subcommand(name.as_str(), |sub| localize_with_prefix(sub, &sub_prefix));
+    }
+
+    command
+}
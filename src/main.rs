@@ -1,7 +1,10 @@
+#[macro_use]
+mod i18n;
 mod commands;
 mod config;
+mod git;
 
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 use config::init_config;
 
 #[derive(Parser)]
@@ -9,6 +12,9 @@ use config::init_config;
 #[command(about = "跨平台命令行工具集，专注于开发工作流优化")]
 #[command(version = env!("CARGO_PKG_VERSION"))]
 struct Cli {
+    /// 界面语言：zh-CN、en（默认读取配置 i18n.lang 或 LANG 环境变量）
+    #[arg(long, global = true, value_name = "LANG")]
+    lang: Option<String>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -19,6 +25,112 @@ enum Commands {
     Gcm {
         /// 提交信息（可选）
         message: Option<String>,
+        /// 批量处理目录下的所有 Git 仓库（含子模块）
+        #[arg(long, value_name = "DIR", conflicts_with = "message")]
+        workspace: Option<String>,
+        /// 签名提交（GPG/SSH）
+        #[arg(short = 'S', long)]
+        sign: bool,
+        /// 追加 Signed-off-by
+        #[arg(short = 's', long)]
+        signoff: bool,
+        /// 追加 Co-authored-by，可重复；支持 "Name <email>" 或按名字匹配已知作者
+        #[arg(long = "co-author", value_name = "AUTHOR")]
+        co_authors: Vec<String>,
+        /// 从配置和最近提交作者中选择结对伙伴
+        #[arg(long, conflicts_with = "workspace")]
+        pair: bool,
+        /// 只输出变更分析结果，不提交
+        #[arg(long, conflicts_with_all = ["message", "workspace"])]
+        analyze: bool,
+        /// 分析结果的输出格式
+        #[arg(long, value_enum, default_value = "text", requires = "analyze")]
+        format: commands::gcm::OutputFormat,
+        /// 严格模式下允许一次提交修改多个包
+        #[arg(long)]
+        allow_cross_package: bool,
+        /// 提交风格：chinese、conventional、gitmoji、angular、sentence（默认读取配置）
+        #[arg(long, conflicts_with = "message")]
+        style: Option<String>,
+    },
+    /// 管理 Git hooks（在普通 git commit 中预填生成的提交信息）
+    Hooks {
+        #[command(subcommand)]
+        action: HooksAction,
+    },
+    /// 查看文件的分类和文件类型
+    Classify {
+        /// 文件路径（相对仓库根目录）
+        #[arg(required = true)]
+        paths: Vec<String>,
+        /// 列出所有命中的规则、优先级以及最终胜出的规则
+        #[arg(long)]
+        explain: bool,
+    },
+    /// 根据规范提交生成 CHANGELOG
+    Changelog {
+        /// 起始版本（默认为上一个标签）
+        #[arg(long)]
+        from: Option<String>,
+        /// 结束版本
+        #[arg(long, default_value = "HEAD")]
+        to: String,
+        /// 段落标题中的版本名（默认取 --to 上的标签，否则为 Unreleased）
+        #[arg(long)]
+        version: Option<String>,
+        /// 输出文件
+        #[arg(short, long, default_value = "CHANGELOG.md")]
+        output: String,
+        /// 只打印生成的段落，不写入文件
+        #[arg(long)]
+        stdout: bool,
+    },
+    /// 根据规范提交计算下一个版本并打标签发布
+    Release {
+        /// 手动指定升级级别（默认根据提交推断）
+        #[arg(long, value_enum)]
+        bump: Option<commands::release::BumpLevel>,
+        /// 预发布标识，如 rc、beta
+        #[arg(long, value_name = "ID")]
+        pre: Option<String>,
+        /// 标签前缀
+        #[arg(long, default_value = "v")]
+        tag_prefix: String,
+        /// 同时更新 CHANGELOG.md
+        #[arg(long)]
+        changelog: bool,
+        /// 推送提交和标签
+        #[arg(long)]
+        push: bool,
+        /// 推送的远程仓库
+        #[arg(long, default_value = "origin")]
+        remote: String,
+        /// 只显示将要执行的操作
+        #[arg(long)]
+        dry_run: bool,
+        /// 跳过确认
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// 拉取上游变更（rebase 或 merge）并推送本地提交
+    Sync {
+        /// 使用 rebase 整合上游变更
+        #[arg(long, conflicts_with = "merge")]
+        rebase: bool,
+        /// 使用 merge 整合上游变更
+        #[arg(long)]
+        merge: bool,
+        /// 只拉取和整合，不推送
+        #[arg(long)]
+        no_push: bool,
+    },
+    /// 保存和恢复工作检查点
+    Wip {
+        /// 检查点保存方式（默认读取配置 wip.mode）
+        #[arg(long, value_enum, global = true)]
+        mode: Option<config::WipMode>,
+        #[command(subcommand)]
+        action: WipAction,
     },
     /// 压缩图片文件
     Compress {
@@ -28,9 +140,72 @@ enum Commands {
         /// 目标路径（可选）
         #[arg(long)]
         dist: Option<String>,
-        /// 压缩比率 0.1-1.0（可选，默认0.8）
-        #[arg(long, default_value_t = 0.8)]
-        rate: f64,
+        /// 编码质量 1-100（JPEG、WebP、AVIF）
+        #[arg(long, default_value_t = 80, value_parser = clap::value_parser!(u8).range(1..=100))]
+        quality: u8,
+        /// 缩放比例 0.1-1.0（默认保持原尺寸）
+        #[arg(long, alias = "rate", conflicts_with_all = ["max_width", "max_height"])]
+        scale: Option<f64>,
+        /// 最大宽度，超出时缩小，不会放大
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        max_width: Option<u32>,
+        /// 最大高度，超出时缩小，不会放大
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        max_height: Option<u32>,
+        /// 适配方式；contain、cover、fill 需要同时指定最大宽高
+        #[arg(long, value_enum, default_value = "inside")]
+        fit: commands::compress::Fit,
+        /// cover 裁剪时保留的区域
+        #[arg(long, value_enum, default_value = "center")]
+        gravity: commands::compress::Gravity,
+        /// 缩放滤镜：lanczos3（默认）、catmull-rom、triangle、nearest（像素画）
+        #[arg(long, value_enum)]
+        filter: Option<config::ResampleFilter>,
+        /// 直接在 sRGB 空间缩放，不转换到线性光
+        #[arg(long)]
+        no_linear: bool,
+        /// 缩小较多时做 USM 锐化
+        #[arg(long)]
+        sharpen: bool,
+        /// 使用 settings.toml 中 [compress.presets.<名称>] 的缩放设置
+        #[arg(long)]
+        preset: Option<String>,
+        /// 处理目录时的并行线程数（默认按 CPU 核数）
+        #[arg(long, short = 'j')]
+        jobs: Option<usize>,
+        /// 元数据策略：strip、keep、keep-copyright、keep-icc
+        #[arg(long, value_enum, default_value = "keep-icc")]
+        metadata: commands::compress::MetadataPolicy,
+        /// 节省比例低于该百分比时保留原文件，如 5
+        #[arg(long, default_value_t = 0.0)]
+        min_savings: f64,
+        /// 输出比原文件大时也写入输出，不保留原文件
+        #[arg(long)]
+        allow_larger: bool,
+        /// 增量压缩：直接写入 --dist，跳过未变化的图片，清理源文件已删除的输出
+        #[arg(long, requires = "dist")]
+        incremental: bool,
+        /// PNG 压缩级别
+        #[arg(long, value_enum, default_value = "best")]
+        png_compression: commands::compress::PngCompression,
+        /// PNG 行过滤策略
+        #[arg(long, value_enum, default_value = "adaptive")]
+        png_filter: commands::compress::PngFilter,
+        /// 转换为指定格式，目标文件扩展名随之改写
+        #[arg(long, value_enum)]
+        format: Option<commands::compress::OutputFormat>,
+        /// WebP 使用无损编码
+        #[arg(long)]
+        lossless: bool,
+        /// 输出原路径到转换后路径的映射：picture 为 HTML 片段，json 为 JSON
+        #[arg(long, value_enum)]
+        emit: Option<commands::compress::EmitKind>,
+        /// 单个文件的体积上限，如 200KB、1.5MB；超出时先降低质量，再缩小尺寸
+        #[arg(long, value_parser = commands::compress::parse_size)]
+        max_bytes: Option<u64>,
+        /// 配合 --max-bytes 使用的最低质量，低于此质量仍无法满足时报错
+        #[arg(long, default_value_t = 40, value_parser = clap::value_parser!(u8).range(1..=100))]
+        min_quality: u8,
     },
     /// 启动静态文件服务器
     Server {
@@ -46,25 +221,162 @@ enum Commands {
     Uninstall,
 }
 
+#[derive(Subcommand)]
+enum HooksAction {
+    /// 安装 prepare-commit-msg hook
+    Install {
+        /// 同时安装 commit-msg 校验 hook
+        #[arg(long)]
+        commit_msg: bool,
+        /// 安装到共享目录并设置 core.hooksPath
+        #[arg(long, value_name = "DIR")]
+        hooks_path: Option<String>,
+    },
+    /// 移除由 cyber-zen 安装的 hooks
+    Uninstall {
+        /// 共享 hooks 目录（默认使用当前生效的目录）
+        #[arg(long, value_name = "DIR")]
+        hooks_path: Option<String>,
+    },
+    /// 由 hook 脚本调用
+    #[command(hide = true)]
+    Run {
+        hook: String,
+        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        args: Vec<String>,
+    },
+}
+
+/// `--lang` 需要在解析参数之前生效，帮助信息才能使用对应语言
+fn lang_from_args() -> Option<String> {
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            break;
+        }
+        if arg == "--lang" {
+            return args.next();
+        }
+        if let Some(lang) = arg.strip_prefix("--lang=") {
+            return Some(lang.to_string());
+        }
+    }
+    None
+}
+
+#[derive(Subcommand)]
+enum WipAction {
+    /// 保存当前工作（含未跟踪文件），描述默认由变更分析生成
+    Save {
+        /// 检查点描述
+        message: Option<String>,
+    },
+    /// 列出当前分支的检查点
+    List,
+    /// 恢复检查点
+    Restore {
+        /// wip list 中的编号（默认最新）
+        index: Option<usize>,
+    },
+    /// 将 ref 模式的检查点合并为一个提交
+    Squash {
+        /// 提交信息（默认自动生成）
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+}
+
 fn main() {
+    i18n::init(lang_from_args().as_deref());
+
     // 初始化配置
     if let Err(e) = init_config() {
-        eprintln!("配置初始化失败: {}", e);
+        eprintln!("{}", t!("main.config_init_failed", error = e));
         std::process::exit(1);
     }
 
-    let cli = Cli::parse();
+    let matches = i18n::localize_command(Cli::command()).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     let result = match cli.command {
-        Commands::Gcm { message } => commands::gcm::run_gcm(message),
-        Commands::Compress { src, dist, rate } => commands::compress::run_compress(src, dist, rate),
+        Commands::Gcm { message, workspace, sign, signoff, co_authors, pair, analyze, format, allow_cross_package, style } => {
+            commands::gcm::run_gcm(commands::gcm::GcmOptions {
+                message,
+                workspace,
+                sign,
+                signoff,
+                co_authors,
+                pair,
+                analyze,
+                format,
+                allow_cross_package,
+                style,
+            })
+        }
+        Commands::Hooks { action } => match action {
+            HooksAction::Install { commit_msg, hooks_path } => commands::hooks::run_hooks_install(commit_msg, hooks_path),
+            HooksAction::Uninstall { hooks_path } => commands::hooks::run_hooks_uninstall(hooks_path),
+            HooksAction::Run { hook, args } => commands::hooks::run_hook(hook, args),
+        },
+        Commands::Changelog { from, to, version, output, stdout } => {
+            commands::changelog::run_changelog(from, to, version, output, stdout)
+        }
+        Commands::Classify { paths, explain } => commands::classify::run_classify(paths, explain),
+        Commands::Compress { src, dist, quality, scale, max_width, max_height, fit, gravity, filter, no_linear, sharpen, preset, jobs, metadata, min_savings, allow_larger, incremental, png_compression, png_filter, format, lossless, emit, max_bytes, min_quality } => {
+            commands::compress::run_compress(commands::compress::CompressOptions {
+                src,
+                dist,
+                resize: commands::compress::ResizeSettings {
+                    scale,
+                    max_width,
+                    max_height,
+                    fit,
+                    gravity,
+                    filter,
+                    linear: no_linear.then_some(false),
+                    sharpen: sharpen.then(config::SharpenSettings::default),
+                },
+                encode: commands::compress::EncodeSettings { quality, lossless, png_compression, png_filter },
+                format,
+                emit,
+                max_bytes,
+                min_quality,
+                preset,
+                jobs,
+                metadata,
+                min_savings,
+                allow_larger,
+                incremental,
+            })
+        }
+        Commands::Release { bump, pre, tag_prefix, changelog, push, remote, dry_run, yes } => {
+            commands::release::run_release(commands::release::ReleaseOptions {
+                bump,
+                pre,
+                tag_prefix,
+                changelog,
+                push,
+                remote,
+                dry_run,
+                yes,
+            })
+        }
+        Commands::Sync { rebase, merge, no_push } => {
+            commands::sync::run_sync(commands::sync::SyncOptions { rebase, merge, no_push })
+        }
+        Commands::Wip { mode, action } => match action {
+            WipAction::Save { message } => commands::wip::run_wip_save(message, mode),
+            WipAction::List => commands::wip::run_wip_list(mode),
+            WipAction::Restore { index } => commands::wip::run_wip_restore(index, mode),
+            WipAction::Squash { message } => commands::wip::run_wip_squash(message, mode),
+        },
         Commands::Server { dir, port } => commands::server::run_server(dir, port),
         Commands::Status => commands::status::run_status(),
         Commands::Uninstall => commands::uninstall::run_uninstall(),
     };
 
     if let Err(e) = result {
-        eprintln!("执行失败: {}", e);
+        eprintln!("{}", t!("main.failed", error = e));
         std::process::exit(1);
     }
 }
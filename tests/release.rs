@@ -0,0 +1,120 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+
+/// 带本地裸仓库作为 origin 的临时仓库，结束时删除
+struct Sandbox {
+    root: PathBuf,
+}
+
+impl Sandbox {
+    fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!("cyber-zen-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("work")).unwrap();
+
+        let sandbox = Sandbox { root };
+        sandbox.git(&sandbox.root, &["init", "-q", "--bare", "remote.git"]);
+        sandbox.git(&sandbox.work(), &["init", "-q"]);
+        sandbox.git(&sandbox.work(), &["remote", "add", "origin", "../remote.git"]);
+        sandbox
+    }
+
+    fn work(&self) -> PathBuf {
+        self.root.join("work")
+    }
+
+    fn git(&self, dir: &Path, args: &[&str]) -> String {
+        let output = self.command("git", dir).args(args).output().unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    fn commit(&self, message: &str) {
+        self.git(&self.work(), &["commit", "-q", "--allow-empty", "-m", message]);
+    }
+
+    fn release(&self, args: &[&str]) -> Output {
+        self.command(env!("CARGO_BIN_EXE_cyber-zen-tools"), &self.work())
+            .args(["--lang", "en", "release"])
+            .args(args)
+            .output()
+            .unwrap()
+    }
+
+    /// 隔离用户配置和 git 全局配置
+    fn command(&self, program: &str, dir: &Path) -> Command {
+        let mut command = Command::new(program);
+        command
+            .current_dir(dir)
+            .env("HOME", &self.root)
+            .env("GIT_CONFIG_NOSYSTEM", "1")
+            .env("GIT_AUTHOR_NAME", "Tester")
+            .env("GIT_AUTHOR_EMAIL", "tester@example.com")
+            .env("GIT_COMMITTER_NAME", "Tester")
+            .env("GIT_COMMITTER_EMAIL", "tester@example.com");
+        command
+    }
+}
+
+impl Drop for Sandbox {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn dry_run_computes_next_version_without_touching_the_remote() {
+    let sandbox = Sandbox::new("release-dry-run");
+    sandbox.commit("feat: initial api");
+    sandbox.git(&sandbox.work(), &["tag", "v0.1.0"]);
+    sandbox.git(&sandbox.work(), &["push", "-q", "origin", "HEAD", "--tags"]);
+    sandbox.commit("feat: add search");
+
+    let output = sandbox.release(&["--push", "--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("v0.2.0"), "{}", stdout);
+
+    assert!(sandbox.git(&sandbox.work(), &["tag", "--list", "v0.2.0"]).is_empty());
+    let remote_tags = sandbox.git(&sandbox.root, &["--git-dir", "remote.git", "tag", "--list"]);
+    assert_eq!(remote_tags.trim(), "v0.1.0");
+}
+
+#[test]
+fn breaking_change_on_pre_release_moves_to_next_major() {
+    let sandbox = Sandbox::new("release-pre");
+    sandbox.commit("feat: initial api");
+    sandbox.git(&sandbox.work(), &["tag", "v1.2.0"]);
+    sandbox.commit("feat: add search");
+    sandbox.git(&sandbox.work(), &["tag", "v1.3.0-rc.1"]);
+    sandbox.git(&sandbox.work(), &["push", "-q", "origin", "HEAD", "--tags"]);
+
+    sandbox.commit("fix: handle empty query");
+    let output = sandbox.release(&["--pre", "rc", "--dry-run"]);
+    assert!(String::from_utf8_lossy(&output.stdout).contains("v1.3.0-rc.2"));
+
+    sandbox.commit("feat!: drop legacy endpoint");
+    let output = sandbox.release(&["--pre", "rc", "--dry-run"]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(stdout.contains("v2.0.0-rc.1"), "{}", stdout);
+}
+
+#[test]
+fn release_tag_message_keeps_changelog_headings() {
+    let sandbox = Sandbox::new("release-tag");
+    sandbox.commit("feat: initial api");
+    sandbox.git(&sandbox.work(), &["tag", "v0.1.0"]);
+    sandbox.commit("feat: add search");
+    sandbox.commit("fix: handle empty query");
+
+    let output = sandbox.release(&["-y"]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+    let tag = sandbox.git(&sandbox.work(), &["cat-file", "-p", "v0.2.0"]);
+    assert!(tag.contains("## v0.2.0"), "{}", tag);
+    assert!(tag.contains("### Features"), "{}", tag);
+    assert!(tag.contains("### Bug Fixes"), "{}", tag);
+    assert!(tag.contains("- add search"), "{}", tag);
+}